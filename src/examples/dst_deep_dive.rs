@@ -14,12 +14,7 @@ fn test_fat_pointer_size() {
     let arr = [1, 2, 3, 4, 5];
     let slice: &[i32] = &arr[1..4];
     // 通过unsafe代码查看切片的内部结构
-    #[repr(C)]
-    struct SliceRepr<T> {
-        data: *const T,
-        len: usize,
-    }
-    let repr = unsafe { &*(&raw const slice as *const SliceRepr<i32>) };
+    let (data, len) = unsafe { raw_slice_repr(slice) };
 
     println!("&str size: {}", std::mem::size_of::<&str>()); // 16字节 👈 比普通指针大一倍
     println!("&[i32] size: {}", std::mem::size_of::<&[i32]>()); // 16字节 👈 包含长度信息
@@ -27,16 +22,84 @@ fn test_fat_pointer_size() {
     println!("*const i32 size: {}", std::mem::size_of::<*const i32>()); // 8字节
 
     println!("slice ptr address: {:p}", slice.as_ptr()); // slice ptr address: 0x7944247fe2f8
-    println!("slice data address: {:p}, len: {}", repr.data, repr.len); // slice data address: 0x7944247fe2f8, len: 3
+    println!("slice data address: {:p}, len: {}", data, len); // slice data address: 0x7944247fe2f8, len: 3
+}
+
+// ✅ 安全地获取胖指针的两部分：数据指针和长度
+fn slice_repr<T>(slice: &[T]) -> (*const T, usize) {
+    (slice.as_ptr(), slice.len())
+}
+
+#[repr(C)]
+struct SliceRepr<T> {
+    data: *const T,
+    len: usize,
+}
+
+/// 直接按内存布局读取胖指针，仅用于演示
+///
+/// # Safety
+///
+/// Rust并不保证`&[T]`的布局是`(data, len)`，这里依赖的是当前编译器的实现细节
+unsafe fn raw_slice_repr<T>(slice: &[T]) -> (*const T, usize) {
+    let repr = unsafe { &*(&raw const slice as *const SliceRepr<T>) };
+    (repr.data, repr.len)
+}
+
+#[test]
+// cargo test --lib -F dst-deep-dive -- test_slice_repr --nocapture
+fn test_slice_repr() {
+    let arr = [1, 2, 3, 4, 5];
+    let slice = &arr[1..4];
+
+    let (ptr, len) = slice_repr(slice);
+    assert_eq!(ptr, &arr[1] as *const i32); // 👈 子切片的数据指针指向原数组中间
+    assert_eq!(len, 3);
+    assert_eq!(unsafe { raw_slice_repr(slice) }, (ptr, len));
+}
+
+// 常见指针类型的内存占用：胖指针 = 数据指针 + 元数据（长度或vtable）
+fn dst_size_report() -> Vec<(&'static str, usize)> {
+    use std::mem::size_of;
+    vec![
+        ("&str", size_of::<&str>()),
+        ("&[i32]", size_of::<&[i32]>()),
+        ("Box<str>", size_of::<Box<str>>()),
+        ("Box<dyn Error>", size_of::<Box<dyn std::error::Error>>()),
+        ("&dyn Display", size_of::<&dyn std::fmt::Display>()),
+        ("&i32", size_of::<&i32>()),
+        ("*const i32", size_of::<*const i32>()),
+        ("String", size_of::<String>()),
+        ("Vec<i32>", size_of::<Vec<i32>>()),
+    ]
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+// cargo test --lib -F dst-deep-dive -- test_dst_size_report --nocapture
+fn test_dst_size_report() {
+    let report = dst_size_report();
+    for (name, size) in &report {
+        println!("{:<16} {}字节", name, size);
+    }
+
+    let size_of = |name: &str| report.iter().find(|(n, _)| *n == name).map(|(_, s)| *s);
+    assert_eq!(size_of("&str"), Some(16));
+    assert_eq!(size_of("Box<dyn Error>"), Some(16));
+    assert_eq!(size_of("&i32"), Some(8));
 }
 
 mod smart_ptr {
+    use std::borrow::Borrow;
     use std::cell::{Cell, RefCell};
     use std::error::Error;
+    use std::hash::{Hash, Hasher};
     use std::ops::Deref;
+    use std::panic::Location;
     use std::rc::Rc;
     use std::sync::Arc;
     use std::sync::atomic::AtomicUsize;
+    use std::time::Instant;
     use std::{fmt, thread};
 
     // 自定义错误类型
@@ -68,166 +131,2719 @@ mod smart_ptr {
             return Err(Box::new(NetworkError("输入为空".to_string())));
         }
 
-        match input.parse::<i32>() {
-            Ok(num) => Ok(num),
-            Err(_) => Err(Box::new(ParseError(format!("无法解析: {}", input)))),
-        }
+        match input.parse::<i32>() {
+            Ok(num) => Ok(num),
+            Err(_) => Err(Box::new(ParseError(format!("无法解析: {}", input)))),
+        }
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_process_data --nocapture
+    fn test_process_data() {
+        assert_eq!(process_data("123").unwrap(), 123);
+        assert!(process_data("no a num").is_err());
+        println!("Box<dyn Error> size: {}", size_of::<Box<dyn Error>>()) // 16字节 👈 [data_ptr, vtable_ptr]
+    }
+
+    fn smart_pointers_in_practice() {
+        // Rc 配置共享场景：多个组件需要同一份配置
+        let config = Rc::new(String::from("production"));
+        // let db_manager = DatabaseManager::new(Rc::clone(&config));
+        // let api_server = ApiServer::new(Rc::clone(&config));
+
+        println!("配置被引用了 {} 次", Rc::strong_count(&config)); // 3次
+
+        // Arc 多线程场景：需要线程安全的共享
+        let shared_counter = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let counter = Arc::clone(&shared_counter);
+                thread::spawn(move || {
+                    // 模拟一些工作
+                    counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                    println!("线程 {} 完成工作", i);
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // RefCell<T> - 内部可变性
+        let mutable_in_immutable = RefCell::new(vec![1, 2, 3]);
+        mutable_in_immutable.borrow_mut().push(4);
+    }
+
+    // 简单的调试智能指针 - 记录访问次数
+    struct DebugBox<T> {
+        data: Box<T>,
+        access_count: Cell<usize>, // 👈 内部可变性，允许在不可变引用中修改
+        locations: RefCell<Vec<&'static Location<'static>>>, // access_at记录的调用位置
+        last_access: Cell<Option<Instant>>,
+    }
+
+    // 某一时刻的访问统计，配合DebugBox::diff计算区间内的访问次数
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct AccessSnapshot {
+        count: usize,
+        last_access: Option<Instant>,
+    }
+
+    impl<T> DebugBox<T> {
+        fn new(data: T) -> Self {
+            Self {
+                data: Box::new(data),
+                access_count: Cell::new(0),
+                locations: RefCell::new(Vec::new()),
+                last_access: Cell::new(None),
+            }
+        }
+
+        fn access_count(&self) -> usize {
+            self.access_count.get()
+        }
+
+        fn snapshot(&self) -> AccessSnapshot {
+            AccessSnapshot {
+                count: self.access_count.get(),
+                last_access: self.last_access.get(),
+            }
+        }
+
+        // 自prev快照以来的访问次数，方便性能分析时只看增量
+        // ⚠️ prev应当来自同一个DebugBox；传入其他实例的快照时最少返回0，不会溢出panic
+        fn diff(&self, prev: &AccessSnapshot) -> usize {
+            self.access_count.get().saturating_sub(prev.count)
+        }
+
+        // ✨ #[track_caller]让Location::caller()返回调用方的位置，而不是这里
+        #[track_caller]
+        fn access_at(&self) -> &T {
+            self.locations.borrow_mut().push(Location::caller());
+            self
+        }
+
+        fn access_locations(&self) -> Vec<String> {
+            self.locations
+                .borrow()
+                .iter()
+                .map(|loc| format!("{}:{}:{}", loc.file(), loc.line(), loc.column()))
+                .collect()
+        }
+    }
+
+    // ✨ 实现Deref让智能指针表现得像普通引用
+    impl<T> Deref for DebugBox<T> {
+        type Target = T;
+
+        fn deref(&self) -> &Self::Target {
+            self.access_count.update(|c| c + 1);
+            self.last_access.set(Some(Instant::now()));
+            &self.data
+        }
+    }
+
+    impl<T: fmt::Display> fmt::Display for DebugBox<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let data: &T = &self;
+            write!(f, "DebugBox({})", data)
+        }
+    }
+
+    // AsRef和Deref一样算作一次访问
+    impl<T> AsRef<T> for DebugBox<T> {
+        fn as_ref(&self) -> &T {
+            self
+        }
+    }
+
+    // ⚠️ Borrow不计数：HashMap在哈希和比较时会频繁调用borrow，计数会失去意义
+    impl<T> Borrow<T> for DebugBox<T> {
+        fn borrow(&self) -> &T {
+            &self.data
+        }
+    }
+
+    // 让DebugBox<String>可以直接用&str查找HashMap
+    impl Borrow<str> for DebugBox<String> {
+        fn borrow(&self) -> &str {
+            &self.data
+        }
+    }
+
+    // Borrow要求Hash/Eq与被借用的类型保持一致，所以这里直接委托给内部数据
+    impl<T: PartialEq> PartialEq for DebugBox<T> {
+        fn eq(&self, other: &Self) -> bool {
+            self.data == other.data
+        }
+    }
+
+    impl<T: Eq> Eq for DebugBox<T> {}
+
+    impl<T: Hash> Hash for DebugBox<T> {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.data.hash(state)
+        }
+    }
+
+    impl<T> Drop for DebugBox<T> {
+        fn drop(&mut self) {
+            println!("🗑️  销毁DebugBox，总共访问了{}次", self.access_count.get());
+        }
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_debug_box --nocapture
+    fn test_debug_box() {
+        let debug_str = DebugBox::new("Hello, Rust!".to_string());
+
+        println!("字符串长度: {}", debug_str.len()); // 触发deref
+        println!("内容: {}", *debug_str); // 再次触发deref
+        println!("显示: {}", debug_str); // Display实现会触发1次deref
+
+        println!("总访问次数: {}", debug_str.access_count()); // 3
+        // 离开作用域时自动调用drop
+    }
+
+    // 销毁时把标签写入共享日志，用来观察多个值的销毁顺序
+    struct DropLogger {
+        label: String,
+        log: Rc<RefCell<Vec<String>>>,
+    }
+
+    impl DropLogger {
+        fn new(label: &str, log: &Rc<RefCell<Vec<String>>>) -> Self {
+            Self {
+                label: label.to_string(),
+                log: Rc::clone(log),
+            }
+        }
+    }
+
+    impl Drop for DropLogger {
+        fn drop(&mut self) {
+            self.log.borrow_mut().push(self.label.clone());
+        }
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_drop_order --nocapture
+    fn test_drop_order() {
+        // ⚠️ 本模块引入了Borrow trait，Rc上的.borrow()会有歧义，所以显式调用RefCell::borrow
+        let log = Rc::new(RefCell::new(Vec::new()));
+        {
+            let _first = DropLogger::new("first", &log);
+            let _second = DropLogger::new("second", &log);
+            let _third = DropLogger::new("third", &log);
+            assert!(RefCell::borrow(&log).is_empty());
+        } // 👈 局部变量按声明的相反顺序销毁（后进先出）
+        assert_eq!(*RefCell::borrow(&log), vec!["third", "second", "first"]);
+
+        // ⚠️ 结构体字段和Vec元素则按声明/存放顺序销毁
+        log.borrow_mut().clear();
+        drop(vec![DropLogger::new("a", &log), DropLogger::new("b", &log)]);
+        assert_eq!(*RefCell::borrow(&log), vec!["a", "b"]);
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_debug_box_access_at --nocapture
+    fn test_debug_box_access_at() {
+        let debug_str = DebugBox::new("Hello".to_string());
+
+        assert_eq!(debug_str.access_at().len(), 5);
+        assert_eq!(debug_str.access_at().as_str(), "Hello");
+
+        let locations = debug_str.access_locations();
+        println!("{:#?}", locations);
+        assert_eq!(locations.len(), 2);
+        assert_ne!(locations[0], locations[1]); // 👈 两次调用在不同的行
+        assert!(locations[0].contains("dst_deep_dive.rs"));
+        assert_eq!(debug_str.access_count(), 2); // access_at同样计数
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_debug_box_snapshot --nocapture
+    fn test_debug_box_snapshot() {
+        let debug_str = DebugBox::new("Hello".to_string());
+        let before = debug_str.snapshot();
+        assert_eq!(before.last_access, None);
+
+        assert_eq!(debug_str.len(), 5);
+        assert!(debug_str.starts_with('H'));
+
+        assert_eq!(debug_str.diff(&before), 2);
+        let after = debug_str.snapshot();
+        assert!(after.last_access.is_some());
+        assert_eq!(debug_str.diff(&after), 0); // 👈 新快照之后还没有访问
+
+        // 别的实例的快照计数更大时不会下溢
+        let fresh = DebugBox::new(String::new());
+        assert_eq!(fresh.diff(&after), 0);
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_debug_box_borrow --nocapture
+    #[allow(clippy::mutable_key_type)] // access_count不参与Hash/Eq，作为key是安全的
+    fn test_debug_box_borrow() {
+        use std::collections::HashMap;
+
+        let mut map = HashMap::new();
+        map.insert(DebugBox::new("alice".to_string()), 1);
+        map.insert(DebugBox::new("bob".to_string()), 2);
+
+        assert_eq!(map.get("alice"), Some(&1)); // 👈 Borrow<str>
+        let bob = String::from("bob");
+        assert_eq!(map.get(&bob), Some(&2)); // 👈 Borrow<String>
+        assert_eq!(map.get("carol"), None);
+
+        // 查找不会增加访问计数，AsRef会
+        let key = map.keys().find(|k| k.data.as_str() == "alice").unwrap();
+        assert_eq!(key.access_count(), 0);
+        assert_eq!(key.as_ref(), "alice");
+        assert_eq!(key.access_count(), 1);
+    }
+}
+
+mod dyn_processor {
+    use std::borrow::Cow;
+    use std::cell::{Cell, RefCell};
+    use std::collections::HashMap;
+    use std::error::Error;
+    use std::fmt;
+    use std::rc::Rc;
+    use std::time::{Duration, Instant};
+
+    // 定义插件接口
+    trait DataProcessor: CloneableProcessor {
+        fn name(&self) -> &str;
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>>;
+        fn priority(&self) -> u8 {
+            5
+        } // 默认优先级
+        // 处理前的格式校验，默认全部通过
+        fn validate(&self, _data: &str) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+        // 调用方已经通过validate时使用，默认仍走process
+        // 在process内部自行校验的处理器应覆盖它，避免重复校验（比如JSON解析两次）
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.process(data)
+        }
+        // 输出与输入相同时可以返回Cow::Borrowed，避免分配
+        fn process_cow<'a>(&self, data: &'a str) -> Result<Cow<'a, str>, Box<dyn Error>> {
+            self.process(data).map(Cow::Owned)
+        }
+        // 处理原始字节，非UTF-8数据直接报错
+        fn process_bytes(&self, data: &[u8]) -> Result<String, Box<dyn Error>> {
+            self.process(std::str::from_utf8(data)?)
+        }
+        // 宽松模式：非法字节替换为U+FFFD后再处理
+        fn process_bytes_lossy(&self, data: &[u8]) -> Result<String, Box<dyn Error>> {
+            self.process(&String::from_utf8_lossy(data))
+        }
+        // 支持的文件扩展名（不含点）
+        fn extensions(&self) -> Vec<&str> {
+            Vec::new()
+        }
+        fn description(&self) -> &str {
+            ""
+        }
+        // 预热：提前完成昂贵的初始化（如编译schema），默认无需预热
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            Ok(())
+        }
+        // 一次性获取处理器的全部描述信息，方便工具展示
+        fn metadata(&self) -> ProcessorMetadata {
+            ProcessorMetadata {
+                name: self.name().to_string(),
+                priority: self.priority(),
+                extensions: self.extensions().into_iter().map(String::from).collect(),
+                description: self.description().to_string(),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct ProcessorMetadata {
+        name: String,
+        priority: u8,
+        extensions: Vec<String>,
+        description: String,
+    }
+
+    // ✨ ProblematicTrait::clone_self返回Self，不是对象安全的
+    // 改为返回Box<dyn DataProcessor>，就能克隆trait对象了
+    trait CloneableProcessor {
+        fn clone_box(&self) -> Box<dyn DataProcessor>;
+    }
+
+    impl<T> CloneableProcessor for T
+    where
+        T: DataProcessor + Clone + 'static, // 👈 所有可Clone的处理器自动获得clone_box
+    {
+        fn clone_box(&self) -> Box<dyn DataProcessor> {
+            Box::new(self.clone())
+        }
+    }
+
+    impl Clone for Box<dyn DataProcessor> {
+        fn clone(&self) -> Self {
+            self.clone_box()
+        }
+    }
+
+    // JSON处理器
+    #[derive(Clone)]
+    struct JsonProcessor {
+        require_object: bool, // 👈 顶层必须是对象，拒绝数组和标量
+        max_depth: usize,     // 👈 允许的最大嵌套深度
+    }
+
+    impl Default for JsonProcessor {
+        fn default() -> Self {
+            Self {
+                require_object: true,
+                max_depth: 32,
+            }
+        }
+    }
+
+    impl JsonProcessor {
+        fn builder() -> JsonProcessorBuilder {
+            JsonProcessorBuilder {
+                processor: Self::default(),
+            }
+        }
+
+        // 标量深度为0，每层对象或数组加1
+        fn depth(value: &serde_json::Value) -> usize {
+            match value {
+                serde_json::Value::Array(items) => {
+                    1 + items.iter().map(Self::depth).max().unwrap_or(0)
+                }
+                serde_json::Value::Object(map) => {
+                    1 + map.values().map(Self::depth).max().unwrap_or(0)
+                }
+                _ => 0,
+            }
+        }
+    }
+
+    struct JsonProcessorBuilder {
+        processor: JsonProcessor,
+    }
+
+    impl JsonProcessorBuilder {
+        fn require_object(mut self, require_object: bool) -> Self {
+            self.processor.require_object = require_object;
+            self
+        }
+
+        fn max_depth(mut self, max_depth: usize) -> Self {
+            self.processor.max_depth = max_depth;
+            self
+        }
+
+        fn build(self) -> JsonProcessor {
+            self.processor
+        }
+    }
+
+    impl DataProcessor for JsonProcessor {
+        fn name(&self) -> &str {
+            "JSON处理器"
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.validate(data)?;
+            self.process_validated(data)
+        }
+
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            Ok(format!("已处理JSON数据: {}", data))
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            let value: serde_json::Value =
+                serde_json::from_str(data).map_err(|_| "不是有效的JSON格式")?;
+            if self.require_object && !value.is_object() {
+                return Err("顶层必须是JSON对象".into());
+            }
+            let depth = Self::depth(&value);
+            if depth > self.max_depth {
+                return Err(format!("JSON嵌套深度{}超过限制{}", depth, self.max_depth).into());
+            }
+            Ok(())
+        }
+
+        fn priority(&self) -> u8 {
+            8
+        }
+
+        fn extensions(&self) -> Vec<&str> {
+            vec!["json"]
+        }
+
+        fn description(&self) -> &str {
+            "校验并处理JSON文档"
+        }
+    }
+
+    // CSV处理器
+    #[derive(Clone)]
+    struct CsvProcessor;
+    impl DataProcessor for CsvProcessor {
+        fn name(&self) -> &str {
+            "CSV处理器"
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.validate(data)?;
+            self.process_validated(data)
+        }
+
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            Ok(format!("已处理{}行CSV数据", data.lines().count()))
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            if data.lines().count() > 1 {
+                Ok(())
+            } else {
+                Err("CSV数据格式不正确".into())
+            }
+        }
+
+        fn priority(&self) -> u8 {
+            6
+        }
+
+        fn extensions(&self) -> Vec<&str> {
+            vec!["csv"]
+        }
+
+        fn description(&self) -> &str {
+            "处理带表头的逗号分隔数据"
+        }
+    }
+
+    impl CsvProcessor {
+        // 第一行作为表头，每个数据行转换为一个 表头 -> 值 的映射
+        // ⚠️ 只按逗号简单切分，不支持带引号或包含逗号的字段
+        fn process_records(
+            &self,
+            data: &str,
+        ) -> Result<Vec<HashMap<String, String>>, Box<dyn Error>> {
+            self.validate(data)?;
+            let mut lines = data.lines();
+            let headers: Vec<&str> = lines
+                .next()
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .collect();
+
+            lines
+                .enumerate()
+                .map(|(idx, line)| {
+                    let values: Vec<&str> = line.split(',').map(str::trim).collect();
+                    if values.len() != headers.len() {
+                        return Err(format!(
+                            "第{}行有{}列，表头有{}列",
+                            idx + 2,
+                            values.len(),
+                            headers.len()
+                        )
+                        .into());
+                    }
+                    Ok(headers
+                        .iter()
+                        .zip(values)
+                        .map(|(h, v)| (h.to_string(), v.to_string()))
+                        .collect())
+                })
+                .collect()
+        }
+    }
+
+    // 格式转换处理器：CSV -> JSON对象数组
+    #[derive(Clone)]
+    struct CsvToJsonProcessor;
+
+    impl DataProcessor for CsvToJsonProcessor {
+        fn name(&self) -> &str {
+            "CSV转JSON处理器"
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            let records = CsvProcessor.process_records(data)?; // 👈 复用CSV解析逻辑
+            Ok(serde_json::to_string(&records)?)
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            CsvProcessor.validate(data)
+        }
+
+        fn priority(&self) -> u8 {
+            7 // 👈 比CsvProcessor高，同时注册时优先转换
+        }
+    }
+
+    // 按JSON Schema校验配置的处理器
+    // ⚠️ 只实现了最小子集：type、required、properties、items
+    #[derive(Clone)]
+    struct SchemaProcessor {
+        schema: serde_json::Value,
+    }
+
+    impl SchemaProcessor {
+        fn new(schema: serde_json::Value) -> Self {
+            Self { schema }
+        }
+
+        // 递归校验，path用于在错误信息中指出是哪个字段
+        fn check(
+            schema: &serde_json::Value,
+            value: &serde_json::Value,
+            path: &str,
+        ) -> Result<(), String> {
+            if let Some(ty) = schema.get("type").and_then(|t| t.as_str()) {
+                let matched = match ty {
+                    "object" => value.is_object(),
+                    "array" => value.is_array(),
+                    "string" => value.is_string(),
+                    "number" => value.is_number(),
+                    "integer" => value.is_i64() || value.is_u64(),
+                    "boolean" => value.is_boolean(),
+                    "null" => value.is_null(),
+                    _ => return Err(format!("不支持的schema类型: {}", ty)),
+                };
+                if !matched {
+                    return Err(format!("{} 的类型应为{}", path, ty));
+                }
+            }
+
+            if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+                for key in required.iter().filter_map(|k| k.as_str()) {
+                    if value.get(key).is_none() {
+                        return Err(format!("{} 缺少必需字段: {}", path, key));
+                    }
+                }
+            }
+
+            if let (Some(properties), Some(object)) = (
+                schema.get("properties").and_then(|p| p.as_object()),
+                value.as_object(),
+            ) {
+                for (key, sub_schema) in properties {
+                    if let Some(field) = object.get(key) {
+                        Self::check(sub_schema, field, &format!("{}.{}", path, key))?;
+                    }
+                }
+            }
+
+            if let (Some(items), Some(array)) = (schema.get("items"), value.as_array()) {
+                for (i, item) in array.iter().enumerate() {
+                    Self::check(items, item, &format!("{}[{}]", path, i))?;
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    impl DataProcessor for SchemaProcessor {
+        fn name(&self) -> &str {
+            "Schema处理器"
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.validate(data)?;
+            Ok("JSON符合schema".to_string())
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            let value: serde_json::Value =
+                serde_json::from_str(data).map_err(|_| "不是有效的JSON格式")?;
+            Self::check(&self.schema, &value, "$")?;
+            Ok(())
+        }
+    }
+
+    // 覆盖处理器优先级的包装器
+    #[derive(Clone)]
+    struct WithPriority<P> {
+        inner: P,
+        priority: u8,
+    }
+
+    impl<P: DataProcessor + Clone + 'static> DataProcessor for WithPriority<P> {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.inner.process(data)
+        }
+
+        fn priority(&self) -> u8 {
+            self.priority // 👈 排序和日志都使用覆盖后的优先级
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            self.inner.validate(data)
+        }
+
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.inner.process_validated(data)
+        }
+
+        fn process_cow<'a>(&self, data: &'a str) -> Result<Cow<'a, str>, Box<dyn Error>> {
+            self.inner.process_cow(data)
+        }
+
+        fn extensions(&self) -> Vec<&str> {
+            self.inner.extensions()
+        }
+
+        fn description(&self) -> &str {
+            self.inner.description()
+        }
+
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            self.inner.warmup()
+        }
+    }
+
+    // 记录每次process耗时的包装器，用于性能诊断
+    #[derive(Clone)]
+    struct Timed<P> {
+        inner: P,
+        last_duration: Cell<Option<Duration>>, // 👈 process只拿到&self，用Cell记录
+    }
+
+    impl<P> Timed<P> {
+        fn new(inner: P) -> Self {
+            Self {
+                inner,
+                last_duration: Cell::new(None),
+            }
+        }
+
+        fn last_duration(&self) -> Option<Duration> {
+            self.last_duration.get()
+        }
+
+        fn timed<R>(&self, f: impl FnOnce() -> R) -> R {
+            let start = Instant::now();
+            let result = f();
+            self.last_duration.set(Some(start.elapsed())); // 👈 失败的调用同样计时
+            result
+        }
+    }
+
+    impl<P: DataProcessor + Clone + 'static> DataProcessor for Timed<P> {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.timed(|| self.inner.process(data))
+        }
+
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.timed(|| self.inner.process_validated(data))
+        }
+
+        fn priority(&self) -> u8 {
+            self.inner.priority()
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            self.inner.validate(data)
+        }
+
+        fn extensions(&self) -> Vec<&str> {
+            self.inner.extensions()
+        }
+
+        fn description(&self) -> &str {
+            self.inner.description()
+        }
+
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            self.inner.warmup()
+        }
+    }
+
+    // 限流错误：当前窗口内的调用次数已用完
+    #[derive(Debug)]
+    struct RateLimitExceeded {
+        limit: usize,
+        window: Duration,
+    }
+
+    impl fmt::Display for RateLimitExceeded {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "超过限流：每{:?}最多调用{}次", self.window, self.limit)
+        }
+    }
+
+    impl Error for RateLimitExceeded {}
+
+    // 限流包装器：每个时间窗口内最多允许limit次process调用
+    #[derive(Clone)]
+    struct RateLimited<P> {
+        inner: P,
+        limit: usize,
+        window: Duration,
+        state: Cell<(Instant, usize)>, // 👈 (当前窗口开始时间, 窗口内已用次数)
+    }
+
+    impl<P> RateLimited<P> {
+        fn new(inner: P, limit: usize, window: Duration) -> Self {
+            Self {
+                inner,
+                limit,
+                window,
+                state: Cell::new((Instant::now(), 0)),
+            }
+        }
+
+        // 取一个令牌，窗口过期时重置计数
+        fn acquire(&self) -> Result<(), RateLimitExceeded> {
+            let (mut start, mut used) = self.state.get();
+            if start.elapsed() >= self.window {
+                start = Instant::now();
+                used = 0;
+            }
+            if used >= self.limit {
+                return Err(RateLimitExceeded {
+                    limit: self.limit,
+                    window: self.window,
+                });
+            }
+            self.state.set((start, used + 1));
+            Ok(())
+        }
+    }
+
+    impl<P: DataProcessor + Clone + 'static> DataProcessor for RateLimited<P> {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.acquire()?;
+            self.inner.process(data)
+        }
+
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.acquire()?;
+            self.inner.process_validated(data)
+        }
+
+        fn priority(&self) -> u8 {
+            self.inner.priority()
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            self.inner.validate(data)
+        }
+
+        fn extensions(&self) -> Vec<&str> {
+            self.inner.extensions()
+        }
+
+        fn description(&self) -> &str {
+            self.inner.description()
+        }
+
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            self.inner.warmup()
+        }
+    }
+
+    // 输入超过大小限制
+    #[derive(Debug)]
+    struct InputTooLarge {
+        size: usize,
+        limit: usize,
+    }
+
+    impl fmt::Display for InputTooLarge {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "输入大小{}字节超过限制{}字节", self.size, self.limit)
+        }
+    }
+
+    impl Error for InputTooLarge {}
+
+    // 限制输入大小的包装器，防止超大输入拖垮内部处理器
+    #[derive(Clone)]
+    struct SizeLimited<P> {
+        inner: P,
+        max_bytes: usize,
+    }
+
+    impl<P> SizeLimited<P> {
+        fn new(inner: P, max_bytes: usize) -> Self {
+            Self { inner, max_bytes }
+        }
+
+        fn check_size(&self, data: &str) -> Result<(), InputTooLarge> {
+            if data.len() > self.max_bytes {
+                return Err(InputTooLarge {
+                    size: data.len(),
+                    limit: self.max_bytes,
+                });
+            }
+            Ok(())
+        }
+    }
+
+    impl<P: DataProcessor + Clone + 'static> DataProcessor for SizeLimited<P> {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.check_size(data)?; // 👈 在交给内部处理器之前拒绝
+            self.inner.process(data)
+        }
+
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.check_size(data)?;
+            self.inner.process_validated(data)
+        }
+
+        fn priority(&self) -> u8 {
+            self.inner.priority()
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            self.check_size(data)?;
+            self.inner.validate(data)
+        }
+
+        fn extensions(&self) -> Vec<&str> {
+            self.inner.extensions()
+        }
+
+        fn description(&self) -> &str {
+            self.inner.description()
+        }
+
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            self.inner.warmup()
+        }
+    }
+
+    // 空白规整包装器：去掉首尾空白，并把内部连续空白压缩成一个空格后再交给内部处理器
+    // ⚠️ 字符串字面量中的连续空白也会被压缩，只适合对空白不敏感的格式
+    #[derive(Clone)]
+    struct Trimmed<P> {
+        inner: P,
+    }
+
+    impl<P> Trimmed<P> {
+        fn new(inner: P) -> Self {
+            Self { inner }
+        }
+
+        fn normalize(data: &str) -> String {
+            data.split_whitespace().collect::<Vec<_>>().join(" ")
+        }
+    }
+
+    impl<P: DataProcessor + Clone + 'static> DataProcessor for Trimmed<P> {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.inner.process(&Self::normalize(data)) // 👈 内部处理器只会看到规整后的输入
+        }
+
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.inner.process_validated(&Self::normalize(data))
+        }
+
+        fn priority(&self) -> u8 {
+            self.inner.priority()
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            self.inner.validate(&Self::normalize(data))
+        }
+
+        fn extensions(&self) -> Vec<&str> {
+            self.inner.extensions()
+        }
+
+        fn description(&self) -> &str {
+            self.inner.description()
+        }
+
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            self.inner.warmup()
+        }
+    }
+
+    // 重试包装器：内部处理器返回的错误被判定为瞬时错误时，等待delay后重试，最多重试max_retries次
+    #[derive(Clone)]
+    struct Retrying<P> {
+        inner: P,
+        max_retries: usize,
+        delay: Duration,
+        is_transient: fn(&str) -> bool, // 👈 按错误信息判断，非瞬时错误立即返回
+    }
+
+    impl<P> Retrying<P> {
+        fn new(
+            inner: P,
+            max_retries: usize,
+            delay: Duration,
+            is_transient: fn(&str) -> bool,
+        ) -> Self {
+            Self {
+                inner,
+                max_retries,
+                delay,
+                is_transient,
+            }
+        }
+
+        fn retry(
+            &self,
+            mut attempt: impl FnMut() -> Result<String, Box<dyn Error>>,
+        ) -> Result<String, Box<dyn Error>> {
+            let mut retries = 0;
+            loop {
+                match attempt() {
+                    Err(e) if retries < self.max_retries && (self.is_transient)(&e.to_string()) => {
+                        retries += 1;
+                        std::thread::sleep(self.delay);
+                    }
+                    result => return result, // 成功、非瞬时错误或重试次数用完
+                }
+            }
+        }
+    }
+
+    impl<P: DataProcessor + Clone + 'static> DataProcessor for Retrying<P> {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.retry(|| self.inner.process(data))
+        }
+
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.retry(|| self.inner.process_validated(data))
+        }
+
+        fn priority(&self) -> u8 {
+            self.inner.priority()
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            self.inner.validate(data)
+        }
+
+        fn extensions(&self) -> Vec<&str> {
+            self.inner.extensions()
+        }
+
+        fn description(&self) -> &str {
+            self.inner.description()
+        }
+
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            self.inner.warmup()
+        }
+    }
+
+    // 旁路缓存包装器：TTL内相同输入直接返回缓存结果，过期后重新处理
+    // 最多缓存max_entries条，满了才清理过期条目，仍然满就淘汰最早写入的一条
+    #[derive(Clone)]
+    struct Cached<P> {
+        inner: P,
+        ttl: Duration,
+        max_entries: usize,
+        cache: RefCell<HashMap<String, (String, Instant)>>, // 👈 输入 -> (结果, 写入时间)
+    }
+
+    impl<P> Cached<P> {
+        const DEFAULT_MAX_ENTRIES: usize = 1024;
+
+        fn new(inner: P, ttl: Duration) -> Self {
+            Self {
+                inner,
+                ttl,
+                max_entries: Self::DEFAULT_MAX_ENTRIES,
+                cache: RefCell::new(HashMap::new()),
+            }
+        }
+
+        fn with_max_entries(mut self, max_entries: usize) -> Self {
+            assert!(max_entries > 0, "max_entries must be non-zero");
+            self.max_entries = max_entries;
+            self
+        }
+
+        fn get_or_process(
+            &self,
+            data: &str,
+            process: impl FnOnce() -> Result<String, Box<dyn Error>>,
+        ) -> Result<String, Box<dyn Error>> {
+            if let Some((output, stored_at)) = self.cache.borrow().get(data)
+                && stored_at.elapsed() < self.ttl
+            {
+                return Ok(output.clone());
+            }
+            let output = process()?; // ⚠️ 只缓存成功的结果
+            self.insert(data, output.clone());
+            Ok(output)
+        }
+
+        // 未满时O(1)插入；只有新key遇到容量上限时才遍历整个map
+        fn insert(&self, data: &str, output: String) {
+            let mut cache = self.cache.borrow_mut();
+            if !cache.contains_key(data) && cache.len() >= self.max_entries {
+                cache.retain(|_, (_, stored_at)| stored_at.elapsed() < self.ttl);
+                if cache.len() >= self.max_entries
+                    && let Some(oldest) = cache
+                        .iter()
+                        .min_by_key(|(_, (_, stored_at))| *stored_at)
+                        .map(|(key, _)| key.clone())
+                {
+                    cache.remove(&oldest); // 👈 没有过期条目可清理，淘汰最早写入的
+                }
+            }
+            cache.insert(data.to_string(), (output, Instant::now()));
+        }
+    }
+
+    impl<P: DataProcessor + Clone + 'static> DataProcessor for Cached<P> {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.get_or_process(data, || self.inner.process(data))
+        }
+
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.get_or_process(data, || self.inner.process_validated(data))
+        }
+
+        fn priority(&self) -> u8 {
+            self.inner.priority()
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            self.inner.validate(data)
+        }
+
+        fn extensions(&self) -> Vec<&str> {
+            self.inner.extensions()
+        }
+
+        fn description(&self) -> &str {
+            self.inner.description()
+        }
+
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            self.inner.warmup()
+        }
+    }
+
+    // 组合处理器：依次执行所有子处理器，全部成功才返回合并后的结果
+    // ⚠️ 与PluginManager的“第一个成功即返回”不同，遇到第一个失败就中止
+    #[derive(Clone)]
+    struct AllOf {
+        name: String,
+        processors: Vec<Box<dyn DataProcessor>>,
+    }
+
+    impl AllOf {
+        fn new(name: impl Into<String>) -> Self {
+            Self {
+                name: name.into(),
+                processors: Vec::new(),
+            }
+        }
+
+        fn with<T: DataProcessor + 'static>(mut self, processor: T) -> Self {
+            self.processors.push(Box::new(processor));
+            self
+        }
+    }
+
+    impl DataProcessor for AllOf {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            let outputs = self
+                .processors
+                .iter()
+                .map(|p| p.process(data)) // 👈 collect到Result时遇到Err立即停止
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(outputs.join("\n"))
+        }
+
+        // validate已经要求所有子处理器都通过，这里直接跳过子处理器的校验
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            let outputs = self
+                .processors
+                .iter()
+                .map(|p| p.process_validated(data))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(outputs.join("\n"))
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            self.processors.iter().try_for_each(|p| p.validate(data))
+        }
+
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            self.processors.iter().try_for_each(|p| p.warmup())
+        }
+    }
+
+    // 组合处理器：依次尝试子处理器，返回第一个成功的结果，全部失败时返回最后一个错误
+    // ✨ 逻辑和PluginManager一样，但本身也是DataProcessor，可以再注册到管理器中
+    #[derive(Clone)]
+    struct AnyOf {
+        name: String,
+        processors: Vec<Box<dyn DataProcessor>>,
+    }
+
+    impl AnyOf {
+        fn new(name: impl Into<String>) -> Self {
+            Self {
+                name: name.into(),
+                processors: Vec::new(),
+            }
+        }
+
+        fn with<T: DataProcessor + 'static>(mut self, processor: T) -> Self {
+            self.processors.push(Box::new(processor));
+            self
+        }
+    }
+
+    impl DataProcessor for AnyOf {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            let mut last_err: Box<dyn Error> = "没有可用的子处理器".into();
+            for processor in &self.processors {
+                match processor.process(data) {
+                    Ok(output) => return Ok(output),
+                    Err(e) => last_err = e,
+                }
+            }
+            Err(last_err)
+        }
+
+        // ⚠️ validate只说明至少有一个子处理器能处理，不知道是哪一个
+        // 所以每个子处理器仍要先validate，再走process_validated（与它自己的process相比不会多校验）
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            let mut last_err: Box<dyn Error> = "没有可用的子处理器".into();
+            for processor in &self.processors {
+                match processor
+                    .validate(data)
+                    .and_then(|_| processor.process_validated(data))
+                {
+                    Ok(output) => return Ok(output),
+                    Err(e) => last_err = e,
+                }
+            }
+            Err(last_err)
+        }
+
+        // 任意一个子处理器校验通过即可，否则返回最后一个校验错误
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            let mut last_err: Box<dyn Error> = "没有可用的子处理器".into();
+            for processor in &self.processors {
+                match processor.validate(data) {
+                    Ok(()) => return Ok(()),
+                    Err(e) => last_err = e,
+                }
+            }
+            Err(last_err)
+        }
+
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            self.processors.iter().try_for_each(|p| p.warmup())
+        }
+    }
+
+    // ✨ 用运算符组合处理器：a | b 得到AnyOf，a & b 得到AllOf
+    // 不能直接为Box<dyn DataProcessor>实现运算符（孤儿规则），所以包一层newtype
+    #[derive(Clone)]
+    struct ProcessorPipeline(Box<dyn DataProcessor>);
+
+    impl<T: DataProcessor + 'static> From<T> for ProcessorPipeline {
+        fn from(processor: T) -> Self {
+            Self(Box::new(processor))
+        }
+    }
+
+    impl ProcessorPipeline {
+        fn into_inner(self) -> Box<dyn DataProcessor> {
+            self.0
+        }
+    }
+
+    impl<T: DataProcessor + 'static> std::ops::BitOr<T> for ProcessorPipeline {
+        type Output = ProcessorPipeline;
+
+        fn bitor(self, rhs: T) -> Self::Output {
+            let name = format!("({} | {})", self.0.name(), rhs.name());
+            ProcessorPipeline(Box::new(AnyOf {
+                name,
+                processors: vec![self.0, Box::new(rhs)],
+            }))
+        }
+    }
+
+    impl<T: DataProcessor + 'static> std::ops::BitAnd<T> for ProcessorPipeline {
+        type Output = ProcessorPipeline;
+
+        fn bitand(self, rhs: T) -> Self::Output {
+            let name = format!("({} & {})", self.0.name(), rhs.name());
+            ProcessorPipeline(Box::new(AllOf {
+                name,
+                processors: vec![self.0, Box::new(rhs)],
+            }))
+        }
+    }
+
+    // 按规则二选一的处理器：路由规则明确时，比按优先级逐个尝试更直接
+    #[derive(Clone)]
+    struct Either<A, B> {
+        name: String,
+        left: A,
+        right: B,
+        rule: fn(&str) -> bool, // 👈 true交给left，false交给right
+    }
+
+    impl<A: DataProcessor, B: DataProcessor> Either<A, B> {
+        fn new(left: A, right: B, rule: fn(&str) -> bool) -> Self {
+            Self {
+                name: format!("{}|{}", left.name(), right.name()),
+                left,
+                right,
+                rule,
+            }
+        }
+
+        fn select(&self, data: &str) -> bool {
+            (self.rule)(data)
+        }
+    }
+
+    impl<A, B> DataProcessor for Either<A, B>
+    where
+        A: DataProcessor + Clone + 'static,
+        B: DataProcessor + Clone + 'static,
+    {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            if self.select(data) {
+                self.left.process(data)
+            } else {
+                self.right.process(data)
+            }
+        }
+
+        // 规则是确定的，validate校验过的分支就是这里会选中的分支
+        fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            if self.select(data) {
+                self.left.process_validated(data)
+            } else {
+                self.right.process_validated(data)
+            }
+        }
+
+        fn priority(&self) -> u8 {
+            self.left.priority().max(self.right.priority())
+        }
+
+        fn validate(&self, data: &str) -> Result<(), Box<dyn Error>> {
+            if self.select(data) {
+                self.left.validate(data)
+            } else {
+                self.right.validate(data)
+            }
+        }
+
+        // 两个分支都可能被选中，所以都要预热
+        fn warmup(&self) -> Result<(), Box<dyn Error>> {
+            self.left.warmup()?;
+            self.right.warmup()
+        }
+    }
+
+    // ✨ 插件管理器：这里是DST的核心应用
+    struct PluginManager {
+        processors: Vec<Box<dyn DataProcessor>>, // 👈 不同类型的处理器统一管理
+        index: HashMap<String, usize>,           // 👈 名称 -> processors中的位置，O(1)查找
+        // 日志级别 👈 0: 静默, 1: 只记录成功的处理器, 2: 记录每次尝试
+        verbosity: u8,
+        log_sink: Option<Rc<RefCell<Vec<String>>>>, // 可选的日志收集器，方便观察输出
+        attempt_hook: Option<AttemptHook>,          // 每次尝试后的回调
+    }
+
+    // 回调参数：(处理器名称, 是否成功)
+    type AttemptHook = Box<dyn Fn(&str, bool)>;
+
+    // ⚠️ Box<dyn Fn>无法克隆，克隆出的管理器不带回调
+    impl Clone for PluginManager {
+        fn clone(&self) -> Self {
+            Self {
+                processors: self.processors.clone(),
+                index: self.index.clone(),
+                verbosity: self.verbosity,
+                log_sink: self.log_sink.clone(),
+                attempt_hook: None,
+            }
+        }
+    }
+
+    impl PluginManager {
+        fn new() -> Self {
+            Self {
+                processors: Vec::new(),
+                index: HashMap::new(),
+                verbosity: 2,
+                log_sink: None,
+                attempt_hook: None,
+            }
+        }
+
+        fn with_verbosity(mut self, level: u8) -> Self {
+            self.verbosity = level;
+            self
+        }
+
+        fn with_log_sink(mut self, sink: Rc<RefCell<Vec<String>>>) -> Self {
+            self.log_sink = Some(sink);
+            self
+        }
+
+        // 只输出不高于当前日志级别的信息
+        fn log(&self, level: u8, message: String) {
+            if level > self.verbosity {
+                return;
+            }
+            println!("{}", message);
+            if let Some(sink) = &self.log_sink {
+                sink.borrow_mut().push(message);
+            }
+        }
+
+        // 注册尝试回调，重复调用会替换之前的回调
+        fn on_attempt<F: Fn(&str, bool) + 'static>(&mut self, hook: F) {
+            self.attempt_hook = Some(Box::new(hook));
+        }
+
+        fn notify_attempt(&self, name: &str, succeeded: bool) {
+            if let Some(hook) = &self.attempt_hook {
+                hook(name, succeeded);
+            }
+        }
+
+        fn register<T>(&mut self, processor: T)
+        where
+            T: DataProcessor + 'static, // 👈 'static保证生命周期安全
+        {
+            self.processors.push(Box::new(processor));
+            self.sort_processors();
+        }
+
+        // 与register相同，但拒绝与已注册处理器同名的处理器
+        fn try_register<T>(&mut self, processor: T) -> Result<(), String>
+        where
+            T: DataProcessor + 'static,
+        {
+            if self.index.contains_key(processor.name()) {
+                return Err(format!("处理器名称冲突: {}", processor.name()));
+            }
+            self.register(processor);
+            Ok(())
+        }
+
+        // 注册时指定优先级，覆盖处理器自身的priority()
+        fn register_with_priority<T>(&mut self, processor: T, priority: u8)
+        where
+            T: DataProcessor + Clone + 'static,
+        {
+            self.register(WithPriority {
+                inner: processor,
+                priority,
+            });
+        }
+
+        fn unregister(&mut self, name: &str) -> Option<Box<dyn DataProcessor>> {
+            let idx = *self.index.get(name)?;
+            let removed = self.processors.remove(idx);
+            self.rebuild_index();
+            Some(removed)
+        }
+
+        fn get(&self, name: &str) -> Option<&dyn DataProcessor> {
+            self.index
+                .get(name)
+                .map(|&idx| self.processors[idx].as_ref())
+        }
+
+        fn clear(&mut self) {
+            self.processors.clear();
+            self.index.clear();
+        }
+
+        fn len(&self) -> usize {
+            self.processors.len()
+        }
+
+        fn is_empty(&self) -> bool {
+            self.processors.is_empty()
+        }
+
+        // 合并另一个管理器的处理器，同名的处理器都保留
+        fn merge(&mut self, other: PluginManager) {
+            self.extend(other.processors);
+        }
+
+        // 合并时丢弃与已有处理器同名的处理器
+        fn merge_dedup(&mut self, other: PluginManager) {
+            for processor in other.processors {
+                if !self.index.contains_key(processor.name()) {
+                    // 👈 先登记到索引，other内部的同名处理器也只保留第一个
+                    self.index
+                        .insert(processor.name().to_string(), self.processors.len());
+                    self.processors.push(processor);
+                }
+            }
+            self.sort_processors();
+        }
+
+        fn list_processors(&self) -> Vec<(&str, u8)> {
+            self.processors
+                .iter()
+                .map(|p| (p.name(), p.priority()))
+                .collect()
+        }
+
+        // 记录当前的处理顺序，便于A/B测试后恢复
+        fn capture_order(&self) -> Vec<String> {
+            self.processors
+                .iter()
+                .map(|p| p.name().to_string())
+                .collect()
+        }
+
+        // 按给定的名称顺序重排处理器，未列出的处理器保持原有相对顺序排在最后
+        // ⚠️ 只是临时覆盖：之后的register/extend/merge等会重新按优先级排序，需要时再调用一次
+        fn apply_order(&mut self, order: &[String]) -> Result<(), String> {
+            if let Some(unknown) = order.iter().find(|name| !self.index.contains_key(*name)) {
+                return Err(format!("未知的处理器: {}", unknown));
+            }
+            self.processors.sort_by_key(|p| {
+                order
+                    .iter()
+                    .position(|name| name == p.name())
+                    .unwrap_or(order.len())
+            }); // 👈 稳定排序
+            self.rebuild_index();
+            Ok(())
+        }
+
+        // 按优先级排序，并保持索引同步
+        fn sort_processors(&mut self) {
+            self.processors
+                .sort_by(|a, b| b.priority().cmp(&a.priority()));
+            self.rebuild_index();
+        }
+
+        fn rebuild_index(&mut self) {
+            self.index.clear();
+            for (idx, processor) in self.processors.iter().enumerate() {
+                // 同名处理器只索引第一个（优先级最高的）
+                self.index
+                    .entry(processor.name().to_string())
+                    .or_insert(idx);
+            }
+        }
+
+        fn process_data(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.try_processors(self.processors.iter(), data)
+        }
+
+        // 从优先级最低（通常最具体）的处理器开始尝试
+        fn process_data_ascending(&self, data: &str) -> Result<String, Box<dyn Error>> {
+            self.try_processors(self.processors.iter().rev(), data)
+        }
+
+        // 试运行：只校验不处理，返回每个处理器能否处理该输入
+        fn validate_all(&self, data: &str) -> Vec<(String, bool)> {
+            self.processors
+                .iter()
+                .map(|p| (p.name().to_string(), p.validate(data).is_ok()))
+                .collect()
+        }
+
+        // 预热所有处理器，不会因为某个失败而中断，最后汇总 (名称, 错误信息)
+        fn warmup_all(&self) -> Result<(), Vec<(String, String)>> {
+            let failures: Vec<_> = self
+                .processors
+                .iter()
+                .filter_map(|p| {
+                    p.warmup()
+                        .err()
+                        .map(|e| (p.name().to_string(), e.to_string()))
+                })
+                .collect();
+            if failures.is_empty() {
+                Ok(())
+            } else {
+                Err(failures)
+            }
+        }
+
+        // 按给定顺序依次尝试，返回第一个成功的结果
+        fn try_processors<'a>(
+            &self,
+            processors: impl Iterator<Item = &'a Box<dyn DataProcessor>>,
+            data: &str,
+        ) -> Result<String, Box<dyn Error>> {
+            if self.is_empty() {
+                return Err("没有注册任何处理器".into());
+            }
+            for processor in processors {
+                self.log(
+                    2,
+                    format!(
+                        "尝试使用 {} (优先级: {})",
+                        processor.name(),
+                        processor.priority()
+                    ),
+                );
+                if let Err(e) = processor.validate(data) {
+                    self.log(2, format!("⚠️ {} 校验未通过: {}", processor.name(), e));
+                    self.notify_attempt(processor.name(), false);
+                    continue; // 👈 校验失败直接跳过，不调用process
+                }
+                // 👈 上面已经校验过，这里不再重复校验
+                match processor.process_validated(data) {
+                    Ok(result) => {
+                        self.log(1, format!("✅ {} 处理成功", processor.name()));
+                        self.notify_attempt(processor.name(), true);
+                        return Ok(result);
+                    }
+                    Err(e) => {
+                        self.log(2, format!("❌ {} 处理失败: {}", processor.name(), e));
+                        self.notify_attempt(processor.name(), false);
+                        continue;
+                    }
+                }
+            }
+            Err("所有处理器都无法处理该数据".into())
+        }
+
+        // 惰性地逐条处理输入流
+        fn process_stream<'a, I>(
+            &'a self,
+            inputs: I,
+        ) -> impl Iterator<Item = Result<String, Box<dyn Error>>> + 'a
+        where
+            I: Iterator<Item = &'a str> + 'a,
+        {
+            inputs.map(move |data| self.process_data(data))
+        }
+
+        // 收集所有输出，遇到第一个失败立即停止，并返回失败输入的下标
+        fn process_stream_collect<'a, I>(
+            &'a self,
+            inputs: I,
+        ) -> Result<Vec<String>, (usize, String)>
+        where
+            I: Iterator<Item = &'a str> + 'a,
+        {
+            self.process_stream(inputs)
+                .enumerate()
+                .map(|(idx, result)| result.map_err(|e| (idx, e.to_string())))
+                .collect()
+        }
+    }
+
+    // 手动实现Debug：只输出名称和优先级，不要求DataProcessor: Debug
+    impl std::fmt::Debug for PluginManager {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("PluginManager")
+                .field("processors", &self.list_processors())
+                .field("verbosity", &self.verbosity)
+                .finish()
+        }
+    }
+
+    // 批量加入处理器：先全部push，最后只排序、重建索引一次
+    impl Extend<Box<dyn DataProcessor>> for PluginManager {
+        fn extend<I: IntoIterator<Item = Box<dyn DataProcessor>>>(&mut self, iter: I) {
+            self.processors.extend(iter);
+            self.sort_processors(); // 👈 不像逐个register那样每次都排序
+        }
+    }
+
+    impl From<Vec<Box<dyn DataProcessor>>> for PluginManager {
+        fn from(processors: Vec<Box<dyn DataProcessor>>) -> Self {
+            let mut manager = PluginManager::new();
+            manager.extend(processors);
+            manager
+        }
+    }
+
+    // 链式注册处理器：PluginManagerBuilder::new().with(a).with(b).build()
+    struct PluginManagerBuilder {
+        manager: PluginManager,
+    }
+
+    impl PluginManagerBuilder {
+        fn new() -> Self {
+            Self {
+                manager: PluginManager::new(),
+            }
+        }
+
+        fn with<T: DataProcessor + 'static>(mut self, processor: T) -> Self {
+            self.manager.register(processor); // 👈 register内部会按优先级排序
+            self
+        }
+
+        fn verbosity(mut self, level: u8) -> Self {
+            self.manager.verbosity = level;
+            self
+        }
+
+        fn build(self) -> PluginManager {
+            self.manager
+        }
+    }
+
+    type ProcessorFactory = Box<dyn Fn() -> Box<dyn DataProcessor>>;
+
+    // 按名称注册构造函数，需要时再创建实例（比如根据配置文件中的名称加载插件）
+    #[derive(Default)]
+    struct ProcessorRegistry {
+        factories: HashMap<String, ProcessorFactory>,
+    }
+
+    impl ProcessorRegistry {
+        fn register_factory(&mut self, name: &str, factory: ProcessorFactory) {
+            self.factories.insert(name.to_string(), factory);
+        }
+
+        // 每次调用都创建一个新实例
+        fn instantiate(&self, name: &str) -> Option<Box<dyn DataProcessor>> {
+            self.factories.get(name).map(|factory| factory())
+        }
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_plugin_system --nocapture
+    fn test_plugin_system() {
+        let mut manager = PluginManager::new();
+
+        // ✨ 注册不同类型的处理器
+        manager.register(JsonProcessor::default());
+        manager.register(CsvProcessor);
+
+        // 测试JSON数据
+        let json_data = r#"{"name": "张三", "age": 30}"#;
+        match manager.process_data(json_data) {
+            Ok(result) => println!("处理结果: {}", result),
+            Err(e) => println!("处理失败: {}", e),
+        }
+        // 测试CSV数据
+        let csv_data = "姓名,年龄\n张三,30\n李四,25";
+        match manager.process_data(csv_data) {
+            Ok(result) => println!("处理结果: {}", result),
+            Err(e) => println!("处理失败: {}", e),
+        }
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_process_stream_collect --nocapture
+    fn test_process_stream_collect() {
+        let mut manager = PluginManager::new();
+        manager.register(JsonProcessor::default());
+        manager.register(CsvProcessor);
+
+        let ok = manager.process_stream_collect([r#"{"id": 1}"#, "a,b\n1,2"].into_iter());
+        assert_eq!(ok.unwrap().len(), 2);
+
+        let inputs = [r#"{"id": 1}"#, "无法识别的数据", "a,b\n1,2"];
+        let (idx, message) = manager
+            .process_stream_collect(inputs.into_iter())
+            .unwrap_err();
+        assert_eq!(idx, 1);
+        assert_eq!(message, "所有处理器都无法处理该数据");
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_plugin_lookup --nocapture
+    fn test_plugin_lookup() {
+        let mut manager = PluginManager::new();
+        manager.register(CsvProcessor);
+        manager.register(JsonProcessor::default());
+
+        let csv = manager.get("CSV处理器").expect("CSV处理器已注册");
+        assert_eq!(csv.name(), "CSV处理器");
+        assert_eq!(csv.priority(), 6);
+        assert!(manager.get("missing").is_none());
+
+        // 注销后索引同步更新
+        assert!(manager.unregister("JSON处理器").is_some());
+        assert!(manager.get("JSON处理器").is_none());
+        assert_eq!(manager.get("CSV处理器").unwrap().name(), "CSV处理器");
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_register_with_priority --nocapture
+    fn test_register_with_priority() {
+        let sink = Rc::new(RefCell::new(Vec::new()));
+        let mut manager = PluginManager::new().with_log_sink(Rc::clone(&sink));
+        manager.register_with_priority(JsonProcessor::default(), 1);
+        manager.register(CsvProcessor);
+
+        assert!(manager.process_data(r#"{"id": 1}"#).is_ok());
+        let logs = sink.borrow();
+        assert_eq!(logs[0], "尝试使用 CSV处理器 (优先级: 6)"); // 👈 CSV先被尝试
+        assert_eq!(logs[2], "尝试使用 JSON处理器 (优先级: 1)");
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_plugin_validate --nocapture
+    fn test_plugin_validate() {
+        // 校验总能通过，但处理总会失败
+        #[derive(Clone)]
+        struct FlakyProcessor;
+        impl DataProcessor for FlakyProcessor {
+            fn name(&self) -> &str {
+                "不稳定处理器"
+            }
+
+            fn process(&self, _data: &str) -> Result<String, Box<dyn Error>> {
+                Err("内部错误".into())
+            }
+        }
+
+        let sink = Rc::new(RefCell::new(Vec::new()));
+        let mut manager = PluginManager::new().with_log_sink(Rc::clone(&sink));
+        manager.register(JsonProcessor::default());
+        manager.register(FlakyProcessor);
+
+        assert!(manager.process_data("纯文本").is_err());
+        assert_eq!(
+            *sink.borrow(),
+            vec![
+                "尝试使用 JSON处理器 (优先级: 8)",
+                "⚠️ JSON处理器 校验未通过: 不是有效的JSON格式",
+                "尝试使用 不稳定处理器 (优先级: 5)",
+                "❌ 不稳定处理器 处理失败: 内部错误",
+            ]
+        );
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_warmup_all --nocapture
+    fn test_warmup_all() {
+        #[derive(Clone)]
+        struct BrokenSchemaProcessor;
+        impl DataProcessor for BrokenSchemaProcessor {
+            fn name(&self) -> &str {
+                "损坏的Schema处理器"
+            }
+
+            fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+                Ok(data.to_string())
+            }
+
+            fn warmup(&self) -> Result<(), Box<dyn Error>> {
+                Err("schema编译失败".into())
+            }
+        }
+
+        let mut manager = PluginManager::new();
+        manager.register(JsonProcessor::default());
+        assert!(manager.warmup_all().is_ok());
+
+        manager.register_with_priority(BrokenSchemaProcessor, 1); // 👈 包装后也会转发warmup
+        assert_eq!(
+            manager.warmup_all(),
+            Err(vec![(
+                "损坏的Schema处理器".to_string(),
+                "schema编译失败".to_string()
+            )])
+        );
+
+        // 其他包装器和组合处理器同样会把预热转发给内部处理器
+        let broken = Err::<(), _>("schema编译失败".to_string());
+        let warmup = |p: &dyn DataProcessor| p.warmup().map_err(|e| e.to_string());
+        assert_eq!(warmup(&Timed::new(BrokenSchemaProcessor)), broken);
+        assert_eq!(
+            warmup(&Cached::new(BrokenSchemaProcessor, Duration::from_secs(1))),
+            broken
+        );
+        let all = AllOf::new("全部")
+            .with(JsonProcessor::default())
+            .with(BrokenSchemaProcessor);
+        assert_eq!(warmup(&all), broken);
+        let any = AnyOf::new("任一")
+            .with(BrokenSchemaProcessor)
+            .with(JsonProcessor::default());
+        assert_eq!(warmup(&any), broken);
+        // Either的两个分支都会预热，即使规则只会选中其中一个
+        let either = Either::new(JsonProcessor::default(), BrokenSchemaProcessor, |_| true);
+        assert_eq!(warmup(&either), broken);
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_process_validated --nocapture
+    fn test_process_validated() {
+        let json = JsonProcessor::default();
+        // process自带校验，process_validated信任调用方已经校验过
+        assert!(json.process("纯文本").is_err());
+        assert_eq!(
+            json.process_validated("纯文本").unwrap(),
+            "已处理JSON数据: 纯文本"
+        );
+
+        // PluginManager先validate再process_validated，结果与直接process一致
+        let mut manager = PluginManager::new();
+        manager.register(JsonProcessor::default());
+        let data = r#"{"id": 1}"#;
+        assert_eq!(
+            manager.process_data(data).unwrap(),
+            json.process(data).unwrap()
+        );
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_process_validated_through_wrappers --nocapture
+    fn test_process_validated_through_wrappers() {
+        // 和JsonProcessor一样在process内部自行校验，并记录校验次数
+        #[derive(Clone)]
+        struct ValidatingProcessor {
+            validations: Rc<Cell<usize>>,
+        }
+        impl DataProcessor for ValidatingProcessor {
+            fn name(&self) -> &str {
+                "校验计数处理器"
+            }
+
+            fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+                self.validate(data)?;
+                self.process_validated(data)
+            }
+
+            fn validate(&self, _data: &str) -> Result<(), Box<dyn Error>> {
+                self.validations.update(|c| c + 1);
+                Ok(())
+            }
+
+            fn process_validated(&self, data: &str) -> Result<String, Box<dyn Error>> {
+                Ok(data.to_string())
+            }
+        }
+
+        let validations = Rc::new(Cell::new(0));
+        let counting = ValidatingProcessor {
+            validations: Rc::clone(&validations),
+        };
+        let second = Duration::from_secs(1);
+        let run = |processor: Box<dyn DataProcessor>| {
+            validations.set(0);
+            let mut manager = PluginManager::new().with_verbosity(0);
+            manager.extend([processor]);
+            manager.process_data("数据").unwrap();
+            validations.get()
+        };
+
+        // 层层包装后，PluginManager的validate + process_validated仍然只校验一次
+        let wrapped = Timed::new(RateLimited::new(
+            SizeLimited::new(
+                Trimmed::new(Retrying::new(
+                    Cached::new(counting.clone(), second),
+                    1,
+                    Duration::ZERO,
+                    |_| true,
+                )),
+                1024,
+            ),
+            10,
+            second,
+        ));
+        assert_eq!(run(Box::new(wrapped)), 1);
+        assert_eq!(run(Box::new(AllOf::new("全部").with(counting.clone()))), 1);
+        let either = Either::new(counting.clone(), CsvProcessor, |_| true);
+        assert_eq!(run(Box::new(either)), 1);
+
+        // ⚠️ AnyOf不记录是哪个子处理器通过了校验，处理前需要再校验一次
+        assert_eq!(run(Box::new(AnyOf::new("任一").with(counting))), 2);
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_process_cow --nocapture
+    fn test_process_cow() {
+        // 原样返回输入的处理器
+        #[derive(Clone)]
+        struct PassthroughProcessor;
+        impl DataProcessor for PassthroughProcessor {
+            fn name(&self) -> &str {
+                "透传处理器"
+            }
+
+            fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+                Ok(data.to_string())
+            }
+
+            fn process_cow<'a>(&self, data: &'a str) -> Result<Cow<'a, str>, Box<dyn Error>> {
+                Ok(Cow::Borrowed(data)) // 👈 零分配
+            }
+        }
+
+        let data = r#"{"id": 1}"#;
+        let passthrough = PassthroughProcessor.process_cow(data).unwrap();
+        assert!(matches!(passthrough, Cow::Borrowed(_)));
+        assert_eq!(passthrough, data);
+
+        let transformed = JsonProcessor::default().process_cow(data).unwrap();
+        assert!(matches!(transformed, Cow::Owned(_)));
+        assert_eq!(transformed, format!("已处理JSON数据: {}", data));
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_process_bytes_lossy --nocapture
+    fn test_process_bytes_lossy() {
+        let data = b"name,age\n\xFF\xFE,30"; // 👈 包含非法的UTF-8序列
+        assert!(CsvProcessor.process_bytes(data).is_err());
+        assert_eq!(
+            CsvProcessor.process_bytes_lossy(data).unwrap(),
+            "已处理2行CSV数据"
+        );
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_csv_process_records --nocapture
+    fn test_csv_process_records() {
+        let records = CsvProcessor
+            .process_records("姓名,年龄\n张三,30\n李四,25")
+            .unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["姓名"], "张三");
+        assert_eq!(records[0]["年龄"], "30");
+        assert_eq!(records[1]["姓名"], "李四");
+        assert_eq!(records[1]["年龄"], "25");
+
+        assert!(CsvProcessor.process_records("姓名,年龄\n张三").is_err()); // 列数不一致
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_json_processor_config --nocapture
+    fn test_json_processor_config() {
+        let nested = r#"{"a": {"b": {"c": {}}}}"#; // 深度为4
+        let shallow = JsonProcessor::builder().max_depth(2).build();
+        let err = shallow.process(nested).unwrap_err();
+        assert_eq!(err.to_string(), "JSON嵌套深度4超过限制2");
+        assert!(JsonProcessor::default().process(nested).is_ok());
+
+        let array = "[1, 2, 3]";
+        let err = JsonProcessor::default().process(array).unwrap_err();
+        assert_eq!(err.to_string(), "顶层必须是JSON对象");
+        let lenient = JsonProcessor::builder().require_object(false).build();
+        assert!(lenient.process(array).is_ok());
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_clone_plugin_manager --nocapture
+    fn test_clone_plugin_manager() {
+        let mut manager = PluginManager::new();
+        manager.register(JsonProcessor::default());
+        manager.register(CsvProcessor);
+
+        let mut cloned = manager.clone(); // 👈 通过clone_box深拷贝所有处理器
+        cloned.unregister("JSON处理器");
+
+        let json_data = r#"{"id": 1}"#;
+        let csv_data = "a,b\n1,2";
+        assert!(manager.process_data(json_data).is_ok());
+        assert!(manager.process_data(csv_data).is_ok());
+        assert!(cloned.process_data(json_data).is_err()); // 克隆体的修改不影响原管理器
+        assert!(cloned.process_data(csv_data).is_ok());
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_processor_order --nocapture
+    fn test_processor_order() {
+        let mut manager = PluginManager::new();
+        manager.register(JsonProcessor::default());
+        manager.register(CsvProcessor);
+
+        let mut order = manager.capture_order();
+        assert_eq!(order, vec!["JSON处理器", "CSV处理器"]);
+
+        order.reverse();
+        manager.apply_order(&order).unwrap();
+        assert_eq!(
+            manager.list_processors(),
+            vec![("CSV处理器", 6), ("JSON处理器", 8)]
+        );
+        assert_eq!(manager.get("CSV处理器").unwrap().name(), "CSV处理器");
+
+        let err = manager.apply_order(&["XML处理器".to_string()]).unwrap_err();
+        assert_eq!(err, "未知的处理器: XML处理器");
+        assert_eq!(manager.capture_order(), order); // 出错时顺序不变
+
+        // 再次注册会按优先级重新排序，自定义顺序被覆盖
+        manager.register(CsvToJsonProcessor);
+        assert_eq!(
+            manager.capture_order(),
+            vec!["JSON处理器", "CSV转JSON处理器", "CSV处理器"]
+        );
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_plugin_manager_debug --nocapture
+    fn test_plugin_manager_debug() {
+        let mut manager = PluginManager::new();
+        manager.register(JsonProcessor::default());
+        manager.register(CsvProcessor);
+
+        let output = format!("{:?}", manager);
+        println!("{}", output);
+        assert!(output.contains("JSON处理器"));
+        assert!(output.contains("CSV处理器"));
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_plugin_manager_clear --nocapture
+    fn test_plugin_manager_clear() {
+        let mut manager = PluginManager::new();
+        manager.register(JsonProcessor::default());
+        manager.register(CsvProcessor);
+        assert_eq!(manager.len(), 2);
+        assert!(!manager.is_empty());
+
+        manager.clear();
+        assert!(manager.is_empty());
+        assert!(manager.get("JSON处理器").is_none());
+        let err = manager.process_data(r#"{"id": 1}"#).unwrap_err();
+        assert_eq!(err.to_string(), "没有注册任何处理器");
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_timed_processor --nocapture
+    fn test_timed_processor() {
+        let timed = Timed::new(CsvProcessor);
+        assert_eq!(timed.last_duration(), None);
+
+        assert!(timed.process("姓名,年龄\n张三,30").is_ok());
+        assert!(timed.last_duration().is_some());
+        assert_eq!(timed.name(), "CSV处理器");
+        assert_eq!(timed.priority(), 6);
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_rate_limited_processor --nocapture
+    fn test_rate_limited_processor() {
+        let window = Duration::from_millis(50);
+        let limited = RateLimited::new(CsvProcessor, 3, window);
+        let data = "姓名,年龄\n张三,30";
+
+        for _ in 0..3 {
+            assert!(limited.process(data).is_ok());
+        }
+        let err = limited.process(data).unwrap_err();
+        assert!(err.downcast_ref::<RateLimitExceeded>().is_some());
+
+        // 窗口过期后重新计数
+        std::thread::sleep(window);
+        assert!(limited.process(data).is_ok());
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_all_of_processor --nocapture
+    fn test_all_of_processor() {
+        let all = AllOf::new("JSON+CSV")
+            .with(JsonProcessor::builder().require_object(false).build())
+            .with(CsvProcessor);
+        assert_eq!(all.name(), "JSON+CSV");
+
+        // 单行JSON能通过JSON处理器，但CSV处理器要求至少两行
+        let err = all.process(r#"{"id": 1}"#).unwrap_err();
+        assert_eq!(err.to_string(), "CSV数据格式不正确");
+
+        // 两行都是合法JSON数组时，两个处理器都成功
+        let output = all.process("[1,\n2]").unwrap();
+        assert_eq!(output, "已处理JSON数据: [1,\n2]\n已处理2行CSV数据");
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_any_of_processor --nocapture
+    fn test_any_of_processor() {
+        let any = AnyOf::new("JSON或CSV")
+            .with(JsonProcessor::default())
+            .with(CsvProcessor);
+
+        // JSON处理器失败后由CSV处理器成功
+        assert_eq!(
+            any.process("姓名,年龄\n张三,30").unwrap(),
+            "已处理2行CSV数据"
+        );
+        // 全部失败时返回最后一个错误
+        let err = any.process("abc").unwrap_err();
+        assert_eq!(err.to_string(), "CSV数据格式不正确");
+
+        // 校验同理：任意一个通过即可
+        assert!(any.validate(r#"{"id": 1}"#).is_ok());
+        let err = any.validate("abc").unwrap_err();
+        assert_eq!(err.to_string(), "CSV数据格式不正确");
+        assert!(AnyOf::new("空").validate("abc").is_err());
+
+        // 组合处理器本身也可以注册到管理器
+        let mut manager = PluginManager::new();
+        manager.register(any);
+        assert!(manager.get("JSON或CSV").is_some());
+    }
+
+    fn user_schema() -> SchemaProcessor {
+        SchemaProcessor::new(serde_json::json!({
+            "type": "object",
+            "required": ["name", "age"],
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            }
+        }))
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_schema_missing_required --nocapture
+    fn test_schema_missing_required() {
+        let processor = user_schema();
+        assert_eq!(
+            processor.process(r#"{"name": "张三", "age": 30}"#).unwrap(),
+            "JSON符合schema"
+        );
+
+        let err = processor.process(r#"{"name": "张三"}"#).unwrap_err();
+        assert_eq!(err.to_string(), "$ 缺少必需字段: age");
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_schema_wrong_type --nocapture
+    fn test_schema_wrong_type() {
+        let processor = user_schema();
+
+        let err = processor
+            .process(r#"{"name": "张三", "age": "三十"}"#)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "$.age 的类型应为integer");
+
+        let err = processor
+            .process(r#"{"name": "张三", "age": 30, "tags": ["a", 1]}"#)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "$.tags[1] 的类型应为string");
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_plugin_manager_merge --nocapture
+    fn test_plugin_manager_merge() {
+        let mut csv_manager = PluginManager::new();
+        csv_manager.register(CsvProcessor);
+        let mut json_manager = PluginManager::new();
+        json_manager.register(JsonProcessor::default());
+
+        csv_manager.merge(json_manager.clone());
+        assert_eq!(
+            csv_manager.list_processors(),
+            vec![("JSON处理器", 8), ("CSV处理器", 6)]
+        );
+        assert!(csv_manager.process_data(r#"{"id": 1}"#).is_ok());
+        assert!(csv_manager.process_data("姓名,年龄\n张三,30").is_ok());
+
+        // merge保留同名处理器，merge_dedup丢弃
+        let mut kept = csv_manager.clone();
+        kept.merge(json_manager.clone());
+        assert_eq!(kept.len(), 3);
+        csv_manager.merge_dedup(json_manager);
+        assert_eq!(csv_manager.len(), 2);
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_either_processor --nocapture
+    fn test_either_processor() {
+        let either = Either::new(JsonProcessor::default(), CsvProcessor, |data| {
+            data.trim_start().starts_with('{')
+        });
+        assert_eq!(either.name(), "JSON处理器|CSV处理器");
+
+        assert!(either.select(r#"{"id": 1}"#));
+        assert_eq!(
+            either.process(r#"{"id": 1}"#).unwrap(),
+            r#"已处理JSON数据: {"id": 1}"#
+        );
+        assert!(!either.select("姓名,年龄\n张三,30"));
+        assert_eq!(
+            either.process("姓名,年龄\n张三,30").unwrap(),
+            "已处理2行CSV数据"
+        );
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_csv_to_json --nocapture
+    fn test_csv_to_json() {
+        let output = CsvToJsonProcessor
+            .process("姓名,年龄\n张三,30\n李四,25")
+            .unwrap();
+        println!("{}", output);
+
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!([
+                { "姓名": "张三", "年龄": "30" },
+                { "姓名": "李四", "年龄": "25" }
+            ])
+        );
+        assert!(CsvToJsonProcessor.process("姓名,年龄").is_err());
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_plugin_attempt_hook --nocapture
+    fn test_plugin_attempt_hook() {
+        let attempts = Rc::new(RefCell::new(Vec::new()));
+        let mut manager = PluginManager::new();
+        manager.register(JsonProcessor::default());
+        manager.register(CsvProcessor);
+
+        let recorder = Rc::clone(&attempts);
+        manager.on_attempt(move |name, succeeded| {
+            recorder.borrow_mut().push((name.to_string(), succeeded));
+        });
+
+        assert!(manager.process_data("姓名,年龄\n张三,30").is_ok());
+        assert_eq!(
+            *attempts.borrow(),
+            vec![
+                ("JSON处理器".to_string(), false),
+                ("CSV处理器".to_string(), true)
+            ]
+        );
     }
 
     #[test]
-    // cargo test --lib -F dst-deep-dive -- test_process_data --nocapture
-    fn test_process_data() {
-        assert_eq!(process_data("123").unwrap(), 123);
-        assert!(process_data("no a num").is_err());
-        println!("Box<dyn Error> size: {}", size_of::<Box<dyn Error>>()) // 16字节 👈 [data_ptr, vtable_ptr]
+    // cargo test --lib -F dst-deep-dive -- test_processor_registry --nocapture
+    fn test_processor_registry() {
+        let mut registry = ProcessorRegistry::default();
+        registry.register_factory("json", Box::new(|| Box::new(JsonProcessor::default())));
+
+        let first = registry.instantiate("json").unwrap();
+        let second = registry.instantiate("json").unwrap();
+        assert!(first.process(r#"{"id": 1}"#).is_ok());
+        assert!(second.process(r#"{"id": 2}"#).is_ok());
+        assert!(registry.instantiate("xml").is_none());
     }
 
-    fn smart_pointers_in_practice() {
-        // Rc 配置共享场景：多个组件需要同一份配置
-        let config = Rc::new(String::from("production"));
-        // let db_manager = DatabaseManager::new(Rc::clone(&config));
-        // let api_server = ApiServer::new(Rc::clone(&config));
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_process_data_ascending --nocapture
+    fn test_process_data_ascending() {
+        let attempts = Rc::new(RefCell::new(Vec::new()));
+        let mut manager = PluginManager::new();
+        manager.register(JsonProcessor::default()); // 优先级8
+        manager.register(CsvProcessor); // 优先级6
 
-        println!("配置被引用了 {} 次", Rc::strong_count(&config)); // 3次
+        let recorder = Rc::clone(&attempts);
+        manager.on_attempt(move |name, succeeded| {
+            recorder.borrow_mut().push((name.to_string(), succeeded));
+        });
 
-        // Arc 多线程场景：需要线程安全的共享
-        let shared_counter = Arc::new(AtomicUsize::new(0));
-        let handles: Vec<_> = (0..4)
-            .map(|i| {
-                let counter = Arc::clone(&shared_counter);
-                thread::spawn(move || {
-                    // 模拟一些工作
-                    counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-                    println!("线程 {} 完成工作", i);
-                })
-            })
-            .collect();
+        let result = manager.process_data_ascending("姓名,年龄\n张三,30");
+        assert_eq!(result.unwrap(), "已处理2行CSV数据");
+        assert_eq!(*attempts.borrow(), vec![("CSV处理器".to_string(), true)]); // 👈 CSV先被尝试
+    }
 
-        for handle in handles {
-            handle.join().unwrap();
-        }
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_size_limited_processor --nocapture
+    fn test_size_limited_processor() {
+        let limited = SizeLimited::new(JsonProcessor::default(), 10);
+
+        let err = limited.process(r#"{"id": 123}"#).unwrap_err(); // 11字节
+        let too_large = err.downcast_ref::<InputTooLarge>().unwrap();
+        assert_eq!((too_large.size, too_large.limit), (11, 10));
+
+        assert_eq!(
+            limited.process(r#"{"id":1}"#).unwrap(),
+            r#"已处理JSON数据: {"id":1}"#
+        );
+    }
 
-        // RefCell<T> - 内部可变性
-        let mutable_in_immutable = RefCell::new(vec![1, 2, 3]);
-        mutable_in_immutable.borrow_mut().push(4);
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_trimmed_processor --nocapture
+    fn test_trimmed_processor() {
+        let trimmed = Trimmed::new(JsonProcessor::default());
+        let messy = "  {\"id\":   1,\n\t\"name\": \"a\"}  \n";
+
+        assert!(trimmed.validate(messy).is_ok());
+        assert_eq!(
+            trimmed.process(messy).unwrap(),
+            r#"已处理JSON数据: {"id": 1, "name": "a"}"#
+        );
+        assert_eq!(trimmed.metadata(), JsonProcessor::default().metadata());
     }
 
-    // 简单的调试智能指针 - 记录访问次数
-    struct DebugBox<T> {
-        data: Box<T>,
-        access_count: Cell<usize>, // 👈 内部可变性，允许在不可变引用中修改
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_try_register --nocapture
+    fn test_try_register() {
+        let mut manager = PluginManager::new();
+        assert!(manager.try_register(JsonProcessor::default()).is_ok());
+
+        let err = manager.try_register(JsonProcessor::default()).unwrap_err();
+        assert!(err.contains("JSON处理器"));
+        assert_eq!(manager.len(), 1); // 👈 冲突的处理器没有被加入
     }
 
-    impl<T> DebugBox<T> {
-        fn new(data: T) -> Self {
-            Self {
-                data: Box::new(data),
-                access_count: Cell::new(0),
-            }
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_plugin_manager_extend --nocapture
+    fn test_plugin_manager_extend() {
+        let processors: Vec<Box<dyn DataProcessor>> = vec![
+            Box::new(CsvProcessor),
+            Box::new(JsonProcessor::default()),
+            Box::new(CsvToJsonProcessor),
+        ];
+
+        let mut manager = PluginManager::new();
+        manager.extend(processors.clone());
+        let expected = vec![("JSON处理器", 8), ("CSV转JSON处理器", 7), ("CSV处理器", 6)];
+        assert_eq!(manager.list_processors(), expected);
+        // 索引在排序后重建，按名称查找的位置是正确的
+        assert_eq!(manager.get("CSV处理器").unwrap().priority(), 6);
+
+        let from_vec = PluginManager::from(processors);
+        assert_eq!(from_vec.list_processors(), expected);
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_retrying_processor --nocapture
+    fn test_retrying_processor() {
+        // 前fail_times次返回瞬时错误，之后成功
+        #[derive(Clone)]
+        struct UnstableProcessor {
+            calls: Rc<Cell<usize>>,
+            fail_times: usize,
         }
+        impl DataProcessor for UnstableProcessor {
+            fn name(&self) -> &str {
+                "不稳定处理器"
+            }
 
-        fn access_count(&self) -> usize {
-            self.access_count.get()
+            fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+                self.calls.update(|c| c + 1);
+                if self.calls.get() <= self.fail_times {
+                    return Err("transient: 连接被重置".into());
+                }
+                Ok(data.to_string())
+            }
         }
+
+        let is_transient = |msg: &str| msg.starts_with("transient");
+        let calls = Rc::new(Cell::new(0));
+        let retrying = Retrying::new(
+            UnstableProcessor {
+                calls: Rc::clone(&calls),
+                fail_times: 2,
+            },
+            3,
+            Duration::from_millis(1),
+            is_transient,
+        );
+        assert_eq!(retrying.process("abc").unwrap(), "abc");
+        assert_eq!(calls.get(), 3); // 👈 失败两次，第三次成功
+        assert_eq!(retrying.metadata().name, "不稳定处理器");
+
+        // 重试次数用完后返回最后一次的错误
+        calls.set(0);
+        let exhausted = Retrying::new(
+            UnstableProcessor {
+                calls: Rc::clone(&calls),
+                fail_times: 5,
+            },
+            1,
+            Duration::from_millis(1),
+            is_transient,
+        );
+        assert!(exhausted.process("abc").is_err());
+        assert_eq!(calls.get(), 2);
+
+        // 非瞬时错误不重试
+        let never_transient =
+            Retrying::new(JsonProcessor::default(), 3, Duration::ZERO, is_transient);
+        assert!(never_transient.process("纯文本").is_err());
     }
 
-    // ✨ 实现Deref让智能指针表现得像普通引用
-    impl<T> Deref for DebugBox<T> {
-        type Target = T;
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_validate_all --nocapture
+    fn test_validate_all() {
+        let mut manager = PluginManager::new();
+        manager.register(JsonProcessor::default());
+        manager.register(CsvProcessor);
 
-        fn deref(&self) -> &Self::Target {
-            self.access_count.update(|c| c + 1);
-            &self.data
-        }
+        assert_eq!(
+            manager.validate_all(r#"{"id": 1}"#),
+            vec![
+                ("JSON处理器".to_string(), true),
+                ("CSV处理器".to_string(), false)
+            ]
+        );
     }
 
-    impl<T: fmt::Display> fmt::Display for DebugBox<T> {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            let data: &T = &self;
-            write!(f, "DebugBox({})", data)
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_cached_processor --nocapture
+    fn test_cached_processor() {
+        // 记录被调用次数的处理器
+        #[derive(Clone)]
+        struct CountingProcessor {
+            calls: Rc<Cell<usize>>,
         }
-    }
+        impl DataProcessor for CountingProcessor {
+            fn name(&self) -> &str {
+                "计数处理器"
+            }
 
-    impl<T> Drop for DebugBox<T> {
-        fn drop(&mut self) {
-            println!("🗑️  销毁DebugBox，总共访问了{}次", self.access_count.get());
+            fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
+                self.calls.update(|c| c + 1);
+                Ok(data.to_uppercase())
+            }
         }
+
+        let calls = Rc::new(Cell::new(0));
+        let ttl = Duration::from_millis(50);
+        let cached = Cached::new(
+            CountingProcessor {
+                calls: Rc::clone(&calls),
+            },
+            ttl,
+        );
+
+        assert_eq!(cached.process("abc").unwrap(), "ABC");
+        assert_eq!(cached.process("abc").unwrap(), "ABC");
+        assert_eq!(calls.get(), 1); // 👈 TTL内命中缓存
+
+        std::thread::sleep(ttl);
+        assert_eq!(cached.process("abc").unwrap(), "ABC");
+        assert_eq!(calls.get(), 2); // 👈 过期后重新处理
+
+        // 达到容量上限时淘汰最早写入的条目
+        let cached = cached.with_max_entries(2);
+        cached.process("def").unwrap();
+        cached.process("ghi").unwrap();
+        assert_eq!(cached.cache.borrow().len(), 2);
+        assert!(!cached.cache.borrow().contains_key("abc"));
+
+        // 满了时先清理过期条目，全部过期就不需要再淘汰
+        std::thread::sleep(ttl);
+        cached.process("jkl").unwrap();
+        assert_eq!(cached.cache.borrow().len(), 1);
+        assert!(cached.cache.borrow().contains_key("jkl"));
     }
 
     #[test]
-    // cargo test --lib -F dst-deep-dive -- test_debug_box --nocapture
-    fn test_debug_box() {
-        let debug_str = DebugBox::new("Hello, Rust!".to_string());
+    // cargo test --lib -F dst-deep-dive -- test_processor_pipeline --nocapture
+    fn test_processor_pipeline() {
+        let any = (ProcessorPipeline::from(JsonProcessor::default()) | CsvProcessor).into_inner();
+        assert_eq!(any.name(), "(JSON处理器 | CSV处理器)");
+        assert!(any.process(r#"{"id": 1}"#).is_ok());
+        assert_eq!(
+            any.process("姓名,年龄\n张三,30").unwrap(),
+            "已处理2行CSV数据"
+        );
+        assert!(any.process("abc").is_err());
+
+        let all = (ProcessorPipeline::from(JsonProcessor::default()) & CsvProcessor).into_inner();
+        assert_eq!(all.name(), "(JSON处理器 & CSV处理器)");
+        assert!(all.process(r#"{"id": 1}"#).is_err()); // 👈 CSV处理器要求至少两行
+    }
 
-        println!("字符串长度: {}", debug_str.len()); // 触发deref
-        println!("内容: {}", *debug_str); // 再次触发deref
-        println!("显示: {}", debug_str); // Display实现会触发1次deref
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_processor_metadata --nocapture
+    fn test_processor_metadata() {
+        let metadata = JsonProcessor::default().metadata();
+        println!("{:?}", metadata);
+        assert_eq!(metadata.name, "JSON处理器");
+        assert_eq!(metadata.priority, 8);
+        assert_eq!(metadata.extensions, vec!["json"]);
+
+        // 没有覆盖extensions/description时使用默认值
+        let metadata = CsvToJsonProcessor.metadata();
+        assert!(metadata.extensions.is_empty());
+        assert!(metadata.description.is_empty());
+
+        // 包装器透传内部处理器的描述信息
+        let json = JsonProcessor::default();
+        let expected = json.metadata();
+        let second = Duration::from_secs(1);
+        assert_eq!(Timed::new(json.clone()).metadata(), expected);
+        assert_eq!(
+            RateLimited::new(json.clone(), 1, second).metadata(),
+            expected
+        );
+        assert_eq!(SizeLimited::new(json.clone(), 1024).metadata(), expected);
+        assert_eq!(Cached::new(json, second).metadata(), expected);
+    }
 
-        println!("总访问次数: {}", debug_str.access_count()); // 3
-        // 离开作用域时自动调用drop
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_plugin_manager_builder --nocapture
+    fn test_plugin_manager_builder() {
+        let manager = PluginManagerBuilder::new()
+            .with(CsvProcessor)
+            .with(JsonProcessor::default())
+            .verbosity(0)
+            .build();
+
+        assert_eq!(
+            manager.list_processors(),
+            vec![("JSON处理器", 8), ("CSV处理器", 6)]
+        );
+        assert!(manager.process_data("姓名,年龄\n张三,30").is_ok());
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_plugin_verbosity --nocapture
+    fn test_plugin_verbosity() {
+        // CSV数据会先经过JSON处理器失败，再由CSV处理器成功：共4条尝试日志
+        let csv_data = "姓名,年龄\n张三,30";
+        for (level, expected) in [(0, 0), (1, 1), (2, 4)] {
+            let sink = Rc::new(RefCell::new(Vec::new()));
+            let mut manager = PluginManager::new()
+                .with_verbosity(level)
+                .with_log_sink(Rc::clone(&sink));
+            manager.register(JsonProcessor::default());
+            manager.register(CsvProcessor);
+
+            assert!(manager.process_data(csv_data).is_ok());
+            assert_eq!(sink.borrow().len(), expected, "verbosity = {}", level);
+        }
     }
 }
 
-mod dyn_processor {
+mod async_processor {
     use std::error::Error;
-
-    // 定义插件接口
-    trait DataProcessor {
-        fn name(&self) -> &str;
-        fn process(&self, data: &str) -> Result<String, Box<dyn Error>>;
-        fn priority(&self) -> u8 {
-            5
-        } // 默认优先级
+    use std::fmt;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::time::Duration;
+    use tokio::sync::Semaphore;
+    use tokio::time::Instant;
+
+    type BoxError = Box<dyn Error + Send + Sync>;
+    // 👈 trait中的async fn不是对象安全的，返回Pin<Box<dyn Future>>才能放进Box<dyn Trait>
+    type ProcessFuture<'a> = Pin<Box<dyn Future<Output = Result<String, BoxError>> + Send + 'a>>;
+
+    // 处理器错误：区分可重试的临时故障和不可重试的格式错误
+    #[derive(Debug)]
+    enum ProcessorError {
+        UnsupportedFormat(String),
+        Timeout,
+        Unavailable(String),
     }
 
-    // JSON处理器
-    struct JsonProcessor;
-    impl DataProcessor for JsonProcessor {
-        fn name(&self) -> &str {
-            "JSON处理器"
+    impl fmt::Display for ProcessorError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                ProcessorError::UnsupportedFormat(msg) => write!(f, "不支持的格式: {}", msg),
+                ProcessorError::Timeout => write!(f, "处理超时"),
+                ProcessorError::Unavailable(msg) => write!(f, "服务暂时不可用: {}", msg),
+            }
         }
+    }
 
-        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
-            // 简化的JSON处理逻辑
-            if data.starts_with('{') && data.ends_with('}') {
-                Ok(format!("已处理JSON数据: {}", data))
-            } else {
-                Err("不是有效的JSON格式".into())
+    impl Error for ProcessorError {}
+
+    impl ProcessorError {
+        fn is_retryable(&self) -> bool {
+            match self {
+                ProcessorError::UnsupportedFormat(_) => false, // 👈 重试也不会成功
+                ProcessorError::Timeout | ProcessorError::Unavailable(_) => true,
             }
         }
+    }
+
+    // 整体处理超过截止时间
+    #[derive(Debug)]
+    struct DeadlineExceeded {
+        tried: usize, // 截止前尝试过的处理器数量
+    }
+
+    impl fmt::Display for DeadlineExceeded {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "超过截止时间，已尝试{}个处理器", self.tried)
+        }
+    }
 
+    impl Error for DeadlineExceeded {}
+
+    // 异步插件接口
+    trait AsyncDataProcessor: Send + Sync {
+        fn name(&self) -> &str;
+        fn process<'a>(&'a self, data: &'a str) -> ProcessFuture<'a>;
         fn priority(&self) -> u8 {
-            8
+            5
         }
     }
 
-    // CSV处理器
-    struct CsvProcessor;
-    impl DataProcessor for CsvProcessor {
+    // 异步JSON处理器：模拟需要IO的处理过程
+    struct AsyncJsonProcessor;
+    impl AsyncDataProcessor for AsyncJsonProcessor {
         fn name(&self) -> &str {
-            "CSV处理器"
+            "异步JSON处理器"
         }
 
-        fn process(&self, data: &str) -> Result<String, Box<dyn Error>> {
-            let lines: Vec<&str> = data.lines().collect();
-            if lines.len() > 1 {
-                Ok(format!("已处理{}行CSV数据", lines.len()))
-            } else {
-                Err("CSV数据格式不正确".into())
-            }
+        fn process<'a>(&'a self, data: &'a str) -> ProcessFuture<'a> {
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await; // 模拟IO
+                if data.starts_with('{') && data.ends_with('}') {
+                    Ok(format!("已处理JSON数据: {}", data))
+                } else {
+                    Err(ProcessorError::UnsupportedFormat("不是有效的JSON格式".into()).into())
+                }
+            })
         }
 
         fn priority(&self) -> u8 {
-            6
+            8
         }
     }
 
-    // ✨ 插件管理器：这里是DST的核心应用
-    struct PluginManager {
-        processors: Vec<Box<dyn DataProcessor>>, // 👈 不同类型的处理器统一管理
+    struct AsyncPluginManager {
+        processors: Vec<Box<dyn AsyncDataProcessor>>,
     }
 
-    impl PluginManager {
+    impl AsyncPluginManager {
         fn new() -> Self {
             Self {
                 processors: Vec::new(),
@@ -236,58 +2852,226 @@ mod dyn_processor {
 
         fn register<T>(&mut self, processor: T)
         where
-            T: DataProcessor + 'static, // 👈 'static保证生命周期安全
+            T: AsyncDataProcessor + 'static,
         {
             self.processors.push(Box::new(processor));
-            // 按优先级排序
             self.processors
-                .sort_by(|a, b| b.priority().cmp(&a.priority()));
+                .sort_by_key(|p| std::cmp::Reverse(p.priority()));
         }
 
-        fn process_data(&self, data: &str) -> Result<String, Box<dyn Error>> {
+        async fn process_data(&self, data: &str) -> Result<String, BoxError> {
             for processor in &self.processors {
-                println!(
-                    "尝试使用 {} (优先级: {})",
-                    processor.name(),
-                    processor.priority()
-                );
-                match processor.process(data) {
-                    Ok(result) => {
-                        println!("✅ {} 处理成功", processor.name());
-                        return Ok(result);
-                    }
-                    Err(e) => {
-                        println!("❌ {} 处理失败: {}", processor.name(), e);
-                        continue;
+                match processor.process(data).await {
+                    Ok(result) => return Ok(result),
+                    Err(e) => println!("❌ {} 处理失败: {}", processor.name(), e),
+                }
+            }
+            Err("所有处理器都无法处理该数据".into())
+        }
+
+        // 限制整体耗时：到达截止时间后不再尝试后续处理器，正在进行的处理也会被取消
+        async fn process_data_with_deadline(
+            &self,
+            data: &str,
+            deadline: Instant,
+        ) -> Result<String, BoxError> {
+            for (tried, processor) in self.processors.iter().enumerate() {
+                if Instant::now() >= deadline {
+                    return Err(DeadlineExceeded { tried }.into());
+                }
+                match tokio::time::timeout_at(deadline, processor.process(data)).await {
+                    Ok(Ok(result)) => return Ok(result),
+                    Ok(Err(e)) => println!("❌ {} 处理失败: {}", processor.name(), e),
+                    Err(_) => return Err(DeadlineExceeded { tried: tried + 1 }.into()), // 👈 处理中途超时
+                }
+            }
+            Err("所有处理器都无法处理该数据".into())
+        }
+
+        // 每个处理器遇到可重试的错误时最多重试max_retries次，其他错误直接换下一个处理器
+        async fn process_with_retry(
+            &self,
+            data: &str,
+            max_retries: usize,
+        ) -> Result<String, BoxError> {
+            for processor in &self.processors {
+                for attempt in 0..=max_retries {
+                    let e = match processor.process(data).await {
+                        Ok(result) => return Ok(result),
+                        Err(e) => e,
+                    };
+                    println!("❌ {} 第{}次处理失败: {}", processor.name(), attempt + 1, e);
+
+                    let retryable = e
+                        .downcast_ref::<ProcessorError>()
+                        .is_some_and(ProcessorError::is_retryable);
+                    if !retryable || attempt == max_retries {
+                        break;
                     }
+                    tokio::time::sleep(Duration::from_millis(10 * (attempt + 1) as u64)).await;
                 }
             }
             Err("所有处理器都无法处理该数据".into())
         }
+
+        // 批量处理：最多同时处理concurrency个输入，结果顺序与输入一致
+        async fn process_batch(
+            &self,
+            inputs: Vec<String>,
+            concurrency: usize,
+        ) -> Vec<Result<String, String>> {
+            let semaphore = Semaphore::new(concurrency.max(1));
+            let tasks = inputs.iter().map(|input| {
+                let semaphore = &semaphore;
+                async move {
+                    let _permit = semaphore.acquire().await.expect("信号量不会被关闭"); // 👈 拿到许可才能开始处理
+                    self.process_data(input).await.map_err(|e| e.to_string())
+                }
+            });
+            futures::future::join_all(tasks).await // 👈 join_all按输入顺序返回结果
+        }
     }
 
-    #[test]
-    // cargo test --lib -F dst-deep-dive -- test_plugin_system --nocapture
-    fn test_plugin_system() {
-        let mut manager = PluginManager::new();
+    #[tokio::test]
+    // cargo test --lib -F dst-deep-dive -- test_process_batch --nocapture
+    async fn test_process_batch() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
 
-        // ✨ 注册不同类型的处理器
-        manager.register(JsonProcessor);
-        manager.register(CsvProcessor);
+        // 记录同时处理中的任务数
+        struct TrackingProcessor {
+            in_flight: Arc<AtomicUsize>,
+            max_in_flight: Arc<AtomicUsize>,
+        }
+        impl AsyncDataProcessor for TrackingProcessor {
+            fn name(&self) -> &str {
+                "并发跟踪处理器"
+            }
 
-        // 测试JSON数据
-        let json_data = r#"{"name": "张三", "age": 30}"#;
-        match manager.process_data(json_data) {
-            Ok(result) => println!("处理结果: {}", result),
-            Err(e) => println!("处理失败: {}", e),
+            fn process<'a>(&'a self, data: &'a str) -> ProcessFuture<'a> {
+                Box::pin(async move {
+                    let current = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    self.max_in_flight.fetch_max(current, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    self.in_flight.fetch_sub(1, Ordering::SeqCst);
+                    Ok(data.to_uppercase())
+                })
+            }
         }
-        // 测试CSV数据
-        let csv_data = "姓名,年龄\n张三,30\n李四,25";
-        match manager.process_data(csv_data) {
-            Ok(result) => println!("处理结果: {}", result),
-            Err(e) => println!("处理失败: {}", e),
+
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let mut manager = AsyncPluginManager::new();
+        manager.register(AsyncJsonProcessor);
+        manager.register(TrackingProcessor {
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            max_in_flight: Arc::clone(&max_in_flight),
+        });
+
+        let inputs: Vec<String> = (0..10).map(|i| format!("item{}", i)).collect();
+        let results = manager.process_batch(inputs, 3).await;
+
+        assert_eq!(results.len(), 10);
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(result.as_deref(), Ok(format!("ITEM{}", i).as_str()));
+        }
+        println!("最大并发数: {}", max_in_flight.load(Ordering::SeqCst));
+        assert!(max_in_flight.load(Ordering::SeqCst) <= 3);
+    }
+
+    // 前fail_times次返回指定错误，之后成功
+    struct FailingProcessor {
+        calls: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        fail_times: usize,
+        error: fn() -> ProcessorError,
+    }
+
+    impl AsyncDataProcessor for FailingProcessor {
+        fn name(&self) -> &str {
+            "会失败的处理器"
+        }
+
+        fn process<'a>(&'a self, data: &'a str) -> ProcessFuture<'a> {
+            Box::pin(async move {
+                let calls = self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+                if calls <= self.fail_times {
+                    return Err((self.error)().into());
+                }
+                Ok(format!("已处理: {}", data))
+            })
         }
     }
+
+    #[tokio::test(start_paused = true)]
+    // cargo test --lib -F dst-deep-dive -- test_process_with_retry --nocapture
+    async fn test_process_with_retry() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // 临时故障：重试两次后成功
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut manager = AsyncPluginManager::new();
+        manager.register(FailingProcessor {
+            calls: Arc::clone(&calls),
+            fail_times: 2,
+            error: || ProcessorError::Unavailable("连接被重置".into()),
+        });
+        let result = manager.process_with_retry("data", 3).await;
+        assert_eq!(result.unwrap(), "已处理: data");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        // 格式错误：不重试
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut manager = AsyncPluginManager::new();
+        manager.register(FailingProcessor {
+            calls: Arc::clone(&calls),
+            fail_times: 2,
+            error: || ProcessorError::UnsupportedFormat("二进制数据".into()),
+        });
+        assert!(manager.process_with_retry("data", 3).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    // cargo test --lib -F dst-deep-dive -- test_process_data_with_deadline --nocapture
+    async fn test_process_data_with_deadline() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // 每次处理耗时50ms，并且总是失败
+        struct SlowProcessor {
+            calls: Arc<AtomicUsize>,
+        }
+        impl AsyncDataProcessor for SlowProcessor {
+            fn name(&self) -> &str {
+                "慢处理器"
+            }
+
+            fn process<'a>(&'a self, _data: &'a str) -> ProcessFuture<'a> {
+                Box::pin(async move {
+                    self.calls.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Err(ProcessorError::Unavailable("后端繁忙".into()).into())
+                })
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut manager = AsyncPluginManager::new();
+        for _ in 0..3 {
+            manager.register(SlowProcessor {
+                calls: Arc::clone(&calls),
+            });
+        }
+
+        // 第1个在50ms失败，第2个在80ms被截止时间打断，第3个不会被尝试
+        let deadline = Instant::now() + Duration::from_millis(80);
+        let err = manager
+            .process_data_with_deadline("data", deadline)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<DeadlineExceeded>().is_some());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
 }
 
 mod dst_examples {
@@ -402,6 +3186,7 @@ mod error_handling_patterns {
         Ok(Data { value: num })
     }
 
+    #[derive(Debug)]
     struct Data {
         value: i32,
     }
@@ -411,10 +3196,73 @@ mod error_handling_patterns {
     fn validate_data(_: &Data) -> Result<(), Box<dyn Error>> {
         Ok(())
     }
+
+    // ✅ 分层解析：外层错误描述“哪里错了”，内层错误通过source()保留“为什么错”
+    #[derive(Debug)]
+    enum ParseError {
+        MissingField(&'static str),
+        InvalidFormat {
+            field: &'static str,
+            source: std::num::ParseIntError, // 👈 保留原始错误，而不是只留下字符串
+        },
+    }
+
+    impl std::fmt::Display for ParseError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                ParseError::MissingField(field) => write!(f, "缺少字段: {}", field),
+                ParseError::InvalidFormat { field, .. } => write!(f, "字段{}格式错误", field),
+            }
+        }
+    }
+
+    impl Error for ParseError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                ParseError::MissingField(_) => None,
+                ParseError::InvalidFormat { source, .. } => Some(source),
+            }
+        }
+    }
+
+    // 解析 "value=42" 格式的输入
+    fn parse_layered(input: &str) -> Result<Data, ParseError> {
+        let raw = input
+            .trim()
+            .strip_prefix("value=")
+            .ok_or(ParseError::MissingField("value"))?;
+        let value = raw
+            .parse::<i32>()
+            .map_err(|source| ParseError::InvalidFormat {
+                field: "value",
+                source,
+            })?;
+        Ok(Data { value })
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_parse_layered --nocapture
+    fn test_parse_layered() {
+        assert_eq!(parse_layered("value=42").unwrap().value, 42);
+        assert!(matches!(
+            parse_layered("count=1"),
+            Err(ParseError::MissingField("value"))
+        ));
+
+        let err = parse_layered("value=abc").unwrap_err();
+        assert_eq!(err.to_string(), "字段value格式错误");
+        let source = err.source().expect("应该保留原始错误");
+        let parse_int = source
+            .downcast_ref::<std::num::ParseIntError>()
+            .expect("source应该是ParseIntError");
+        assert_eq!(parse_int, &"abc".parse::<i32>().unwrap_err());
+    }
 }
 
 mod dst_usage {
+    use std::collections::HashSet;
     use std::fmt::Display;
+    use std::rc::Rc;
 
     fn str_distinction_demo() {
         // &str - 对str的借用，16字节胖指针
@@ -428,6 +3276,37 @@ mod dst_usage {
         let boxed: Box<str> = "hello".into(); // 拥有数据，但固定大小
     }
 
+    // 字符串驻留池：相同的字符串只分配一次
+    #[derive(Default)]
+    struct StrPool {
+        strings: HashSet<Rc<str>>, // 👈 Rc<str>实现了Borrow<str>，可以直接用&str查找
+    }
+
+    impl StrPool {
+        fn intern(&mut self, s: &str) -> Rc<str> {
+            if let Some(existing) = self.strings.get(s) {
+                return Rc::clone(existing);
+            }
+            let boxed: Box<str> = s.into(); // 固定大小，没有多余的容量
+            let interned: Rc<str> = Rc::from(boxed);
+            self.strings.insert(Rc::clone(&interned));
+            interned
+        }
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_str_pool --nocapture
+    fn test_str_pool() {
+        let mut pool = StrPool::default();
+        let a = pool.intern("hello");
+        let b = pool.intern("hello");
+        let c = pool.intern("world");
+
+        assert!(Rc::ptr_eq(&a, &b)); // 👈 同一块内存
+        assert!(!Rc::ptr_eq(&a, &c));
+        println!("\"hello\" 的引用计数: {}", Rc::strong_count(&a)); // 3: a、b 和池本身
+    }
+
     fn dst_simple_usage() {
         // 从简单到复杂，按需使用
         let simple: [i32; 3] = [1, 2, 3]; // 零开销
@@ -455,4 +3334,32 @@ mod dst_usage {
             println!("值: {}", value);
         }
     }
+
+    // ✅ 安全版本：越界时返回None
+    fn checked_offset<T>(slice: &[T], offset: usize) -> Option<&T> {
+        slice.get(offset)
+    }
+
+    /// 不做边界检查的版本
+    ///
+    /// # Safety
+    ///
+    /// 调用者必须保证 `offset < slice.len()`，否则会读取切片之外的内存（未定义行为）
+    unsafe fn unchecked_offset<T>(slice: &[T], offset: usize) -> &T {
+        debug_assert!(offset < slice.len());
+        unsafe { &*slice.as_ptr().add(offset) } // 👈 安全责任转移给调用者
+    }
+
+    #[test]
+    // cargo test --lib -F dst-deep-dive -- test_checked_offset --nocapture
+    fn test_checked_offset() {
+        let data = [1, 2, 3];
+        assert_eq!(checked_offset(&data, 0), Some(&1));
+        assert_eq!(checked_offset(&data, 2), Some(&3));
+        assert_eq!(checked_offset(&data, 3), None); // 越界
+        assert_eq!(checked_offset(&data, 10), None);
+
+        // SAFETY: 1 < data.len()
+        assert_eq!(unsafe { *unchecked_offset(&data, 1) }, 2);
+    }
 }
@@ -18,16 +18,128 @@ impl Error for MyError {
     }
 }
 
+impl MyError {
+    // Display只输出自身信息，需要完整错误链时使用full_message
+    fn full_message(&self) -> String {
+        let mut message = self.to_string();
+        let mut source = self.source();
+        while let Some(cause) = source {
+            message.push_str(&format!(" (caused by: {})", cause));
+            source = cause.source();
+        }
+        message
+    }
+}
+
+#[test]
+// cargo test --lib -F error-handling-patterns -- test_my_error_full_message --nocapture
+fn test_my_error_full_message() {
+    let io_error = io::Error::new(io::ErrorKind::NotFound, "config.toml不存在");
+    let err = MyError("加载配置失败".to_string(), Box::new(io_error));
+
+    let message = err.full_message();
+    println!("{}", message);
+    assert_eq!(err.to_string(), "MyError occurred: 加载配置失败");
+    assert!(message.contains("加载配置失败"));
+    assert!(message.contains("(caused by: config.toml不存在)"));
+}
+
+// 从trait对象中恢复具体错误类型
+fn downcast_error<'a, E: Error + 'static>(err: &'a (dyn Error + 'static)) -> Option<&'a E> {
+    err.downcast_ref::<E>()
+}
+
+// 沿着source()错误链查找第一个指定类型的错误
+fn find_in_chain<'a, E: Error + 'static>(err: &'a (dyn Error + 'static)) -> Option<&'a E> {
+    let mut current = Some(err);
+    while let Some(e) = current {
+        if let Some(found) = downcast_error::<E>(e) {
+            return Some(found);
+        }
+        current = e.source();
+    }
+    None
+}
+
+#[test]
+// cargo test --lib -F error-handling-patterns -- test_find_in_chain --nocapture
+fn test_find_in_chain() {
+    let inner = MyError("查询用户失败".to_string(), Box::new(QueryError::NotFound));
+    let outer = MyError("加载页面失败".to_string(), Box::new(inner));
+    let err: &(dyn Error + 'static) = &outer;
+
+    assert!(downcast_error::<MyError>(err).is_some());
+    assert!(downcast_error::<QueryError>(err).is_none()); // 👈 只检查最外层
+
+    let found = find_in_chain::<QueryError>(err).expect("错误链中包含QueryError");
+    assert!(matches!(found, QueryError::NotFound));
+    assert!(find_in_chain::<io::Error>(err).is_none());
+}
+
 // 通过enum实现一个场景中完整的错误类型
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub enum QueryError {
     NotFound,
     ParamError(String),
-    IOError(io::Error),
+    IOError(#[serde(with = "io_error_serde")] io::Error), // 👈 io::Error本身不支持serde
     // ...
     OtherError(String), // 处理未预见的其他错误
 }
 
+// io::Error序列化为 {"kind": "NotFound", "message": "..."}，反序列化时还原ErrorKind
+mod io_error_serde {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::io;
+
+    #[derive(Serialize, Deserialize)]
+    struct IoErrorRepr {
+        kind: String,
+        message: String,
+    }
+
+    pub fn serialize<S: Serializer>(e: &io::Error, serializer: S) -> Result<S::Ok, S::Error> {
+        IoErrorRepr {
+            kind: format!("{:?}", e.kind()),
+            message: e.to_string(),
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<io::Error, D::Error> {
+        let repr = IoErrorRepr::deserialize(deserializer)?;
+        // ⚠️ 只还原常见的ErrorKind，其他的归为Other
+        let kind = match repr.kind.as_str() {
+            "NotFound" => io::ErrorKind::NotFound,
+            "PermissionDenied" => io::ErrorKind::PermissionDenied,
+            "AlreadyExists" => io::ErrorKind::AlreadyExists,
+            "InvalidInput" => io::ErrorKind::InvalidInput,
+            "InvalidData" => io::ErrorKind::InvalidData,
+            "TimedOut" => io::ErrorKind::TimedOut,
+            "UnexpectedEof" => io::ErrorKind::UnexpectedEof,
+            _ => io::ErrorKind::Other,
+        };
+        Ok(io::Error::new(kind, repr.message))
+    }
+}
+
+#[test]
+// cargo test --lib -F error-handling-patterns -- test_query_error_serde --nocapture
+fn test_query_error_serde() {
+    let err = QueryError::ParamError("id不能为空".to_string());
+    let json = serde_json::to_string(&err).unwrap();
+    println!("{}", json);
+    let back: QueryError = serde_json::from_str(&json).unwrap();
+    assert!(matches!(back, QueryError::ParamError(ref msg) if msg == "id不能为空"));
+
+    let err = QueryError::IOError(io::Error::new(io::ErrorKind::NotFound, "config.toml不存在"));
+    let value = serde_json::to_value(&err).unwrap();
+    assert_eq!(value["IOError"]["kind"], "NotFound");
+    assert_eq!(value["IOError"]["message"], "config.toml不存在");
+
+    let back: QueryError = serde_json::from_value(value).unwrap();
+    assert!(matches!(back, QueryError::IOError(ref e) if e.kind() == io::ErrorKind::NotFound));
+}
+
 impl std::fmt::Display for QueryError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -48,14 +160,123 @@ impl Error for QueryError {
     }
 }
 
+// 错误分类：调用方无需知道具体错误类型，就能决定是提示用户修正输入还是告警重试
+pub trait Classify {
+    // 调用方的问题（参数错误、资源不存在等），重试没有意义
+    fn is_client_error(&self) -> bool;
+
+    // 服务端的问题（IO、依赖服务故障等）
+    fn is_server_error(&self) -> bool {
+        !self.is_client_error()
+    }
+}
+
+impl Classify for QueryError {
+    fn is_client_error(&self) -> bool {
+        matches!(self, QueryError::NotFound | QueryError::ParamError(_))
+    }
+}
+
+#[test]
+// cargo test --lib -F error-handling-patterns -- test_classify_query_error --nocapture
+fn test_classify_query_error() {
+    let err = QueryError::ParamError("id不能为空".to_string());
+    assert!(err.is_client_error());
+    assert!(!err.is_server_error());
+
+    let err = QueryError::IOError(io::Error::other("磁盘已满"));
+    assert!(err.is_server_error());
+    assert!(!err.is_client_error());
+
+    // 泛型代码只依赖Classify，不关心具体的错误类型
+    fn should_alert<E: Classify>(err: &E) -> bool {
+        err.is_server_error()
+    }
+    assert!(!should_alert(&FileError::Validation(
+        "数字不能为负".to_string()
+    )));
+    assert!(should_alert(&FileError::Io(io::Error::other("读取失败"))));
+}
+
+// 从错误码字符串还原错误（比如解析错误日志），未知的错误码归为OtherError
+impl std::str::FromStr for QueryError {
+    type Err = std::convert::Infallible; // 👈 总能解析成功
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "NOT_FOUND" {
+            return Ok(QueryError::NotFound);
+        }
+        if let Some(msg) = s.strip_prefix("PARAM_ERROR:") {
+            return Ok(QueryError::ParamError(msg.trim().to_string()));
+        }
+        if let Some(msg) = s.strip_prefix("IO_ERROR:") {
+            return Ok(QueryError::IOError(io::Error::other(
+                msg.trim().to_string(),
+            )));
+        }
+        Ok(QueryError::OtherError(s.to_string()))
+    }
+}
+
+#[test]
+// cargo test --lib -F error-handling-patterns -- test_query_error_from_str --nocapture
+fn test_query_error_from_str() {
+    let err: QueryError = "NOT_FOUND".parse().unwrap();
+    assert!(matches!(err, QueryError::NotFound));
+
+    let err: QueryError = "PARAM_ERROR: id不能为空".parse().unwrap();
+    assert!(matches!(err, QueryError::ParamError(ref msg) if msg == "id不能为空"));
+
+    let err: QueryError = "连接被重置".parse().unwrap();
+    assert!(matches!(err, QueryError::OtherError(ref msg) if msg == "连接被重置"));
+}
+
 // Error使用From trait进行类型转换
 #[derive(Debug)]
-enum FileError {
+pub enum FileError {
     Io(std::io::Error),
     Parse(std::num::ParseIntError),
     Validation(String),
 }
 
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FileError::Io(e) => write!(f, "文件读取失败: {}", e),
+            FileError::Parse(e) => write!(f, "数字解析失败: {}", e),
+            FileError::Validation(msg) => write!(f, "数据校验失败: {}", msg),
+        }
+    }
+}
+
+impl Error for FileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FileError::Io(e) => Some(e),
+            FileError::Parse(e) => Some(e),
+            FileError::Validation(_) => None,
+        }
+    }
+}
+
+impl FileError {
+    // 对应ErrorCatalog中的错误码：IO问题属于服务端，内容问题属于客户端输入
+    pub fn code(&self) -> &'static str {
+        match self {
+            FileError::Io(_) => "FILE_IO_ERROR",
+            FileError::Parse(_) | FileError::Validation(_) => "FILE_CONTENT_INVALID",
+        }
+    }
+}
+
+impl Classify for FileError {
+    // 与code()的划分一致：内容问题是客户端输入导致的
+    fn is_client_error(&self) -> bool {
+        matches!(self, FileError::Parse(_) | FileError::Validation(_))
+    }
+}
+
 // 实现From trait后，?操作符会自动转换
 impl From<std::io::Error> for FileError {
     fn from(error: std::io::Error) -> Self {
@@ -80,6 +301,29 @@ fn read_and_parse_number(path: &str) -> Result<i32, FileError> {
     Ok(number)
 }
 
+// 通用的校验组合子：条件满足返回Ok(value)，否则返回指定的错误
+fn validate<T, E, F: Fn(&T) -> bool>(value: T, pred: F, err: E) -> Result<T, E> {
+    if pred(&value) { Ok(value) } else { Err(err) }
+}
+
+#[test]
+// cargo test --lib -F error-handling-patterns -- test_validate --nocapture
+fn test_validate() {
+    let ok = validate(
+        42,
+        |n| *n >= 0,
+        FileError::Validation("数字不能为负".to_string()),
+    );
+    assert_eq!(ok.unwrap(), 42);
+
+    let err = validate(
+        -1,
+        |n| *n >= 0,
+        FileError::Validation("数字不能为负".to_string()),
+    );
+    assert!(matches!(err, Err(FileError::Validation(msg)) if msg == "数字不能为负"));
+}
+
 // 永远不要使用字符串作为错误类型
 // ❌ 字符串错误的问题
 fn parse_number_bad(s: &str) -> Result<i32, &'static str> {
@@ -94,6 +338,7 @@ fn parse_number_bad(s: &str) -> Result<i32, &'static str> {
 enum ParseError {
     Empty,
     InvalidFormat(std::num::ParseIntError),
+    Custom(String), // 其他无法归类的解析问题，只携带描述信息
 }
 
 impl std::fmt::Display for ParseError {
@@ -101,6 +346,7 @@ impl std::fmt::Display for ParseError {
         match self {
             ParseError::Empty => write!(f, "输入不能为空"),
             ParseError::InvalidFormat(e) => write!(f, "数字格式错误: {}", e),
+            ParseError::Custom(msg) => write!(f, "解析失败: {}", msg),
         }
     }
 }
@@ -108,17 +354,68 @@ impl std::fmt::Display for ParseError {
 impl std::error::Error for ParseError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            ParseError::Empty => None,
+            ParseError::Empty | ParseError::Custom(_) => None,
             ParseError::InvalidFormat(e) => Some(e), // 👈 保持错误链
         }
     }
 }
 
-fn parse_number_good(s: &str) -> Result<i32, ParseError> {
-    if s.is_empty() {
-        return Err(ParseError::Empty);
+impl From<std::num::ParseIntError> for ParseError {
+    fn from(error: std::num::ParseIntError) -> Self {
+        ParseError::InvalidFormat(error)
+    }
+}
+
+// 便捷构造：ParseError::from("超出范围") 或者 Err("超出范围".into())
+impl From<&str> for ParseError {
+    fn from(msg: &str) -> Self {
+        ParseError::Custom(msg.to_string())
+    }
+}
+
+impl From<String> for ParseError {
+    fn from(msg: String) -> Self {
+        ParseError::Custom(msg)
     }
-    s.parse().map_err(ParseError::InvalidFormat)
+}
+
+impl ParseError {
+    // 统一的解析入口：空输入 -> Empty，格式错误 -> InvalidFormat
+    fn from_parse(s: &str) -> Result<i32, ParseError> {
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        Ok(s.parse()?) // 👈 ParseIntError -> ParseError
+    }
+}
+
+fn parse_number_good(s: &str) -> Result<i32, ParseError> {
+    ParseError::from_parse(s)
+}
+
+#[test]
+// cargo test --lib -F error-handling-patterns -- test_parse_error_from_parse --nocapture
+fn test_parse_error_from_parse() {
+    assert!(matches!(ParseError::from_parse(""), Err(ParseError::Empty)));
+    assert_eq!(ParseError::from_parse("42").unwrap(), 42);
+
+    let err = parse_number_good("x").unwrap_err();
+    assert!(matches!(err, ParseError::InvalidFormat(_)));
+    let source = err.source().expect("InvalidFormat应该保留错误链");
+    assert!(source.is::<std::num::ParseIntError>());
+    println!("错误: {}, 原因: {}", err, source);
+}
+
+#[test]
+// cargo test --lib -F error-handling-patterns -- test_parse_error_from_str --nocapture
+fn test_parse_error_from_str() {
+    let err = ParseError::from("超出范围");
+    assert!(matches!(err, ParseError::Custom(ref msg) if msg == "超出范围"));
+    assert_eq!(err.to_string(), "解析失败: 超出范围");
+    assert!(err.source().is_none());
+
+    let result: Result<i32, ParseError> = Err(format!("第{}行格式错误", 3).into());
+    assert!(matches!(result, Err(ParseError::Custom(ref msg)) if msg == "第3行格式错误"));
 }
 
 mod use_thiserror {
@@ -139,6 +436,13 @@ mod use_thiserror {
         Serialization(#[from] serde_json::Error),
     }
 
+    impl super::Classify for DatabaseError {
+        // 只有空查询是调用方传入的问题，其余都来自数据库或本地环境
+        fn is_client_error(&self) -> bool {
+            matches!(self, DatabaseError::Query { .. })
+        }
+    }
+
     // 使用示例
     fn execute_query(query: &str) -> Result<String, DatabaseError> {
         if query.trim().is_empty() {
@@ -157,33 +461,51 @@ mod use_thiserror {
 
 mod use_anyhow {
     use anyhow::{bail, Context, Result};
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
 
-    fn process_user_data(user_id: u32) -> Result<UserProfile> {
+    fn process_user_data(repo: &dyn UserRepository, user_id: u32) -> Result<UserProfile> {
         if user_id == 0 {
             bail!("用户ID不能为0"); // 👈 直接生成错误
         }
 
-        let user = fetch_user(user_id).with_context(|| format!("获取用户{}失败", user_id))?; // 👈 添加错误上下文信息
+        let user = fetch_user(repo, user_id).with_context(|| format!("获取用户{}失败", user_id))?; // 👈 添加错误上下文信息
 
         let profile = build_profile(&user).context("构建用户档案失败")?; // 👈 添加错误上下文信息
 
         Ok(profile)
     }
 
-    fn fetch_user(user_id: u32) -> Result<User> {
-        // 模拟数据库查询
-        if user_id == 999 {
-            bail!("用户{}不存在", user_id);
+    // 数据访问抽象：业务逻辑不关心数据来自数据库还是内存
+    pub trait UserRepository {
+        fn find(&self, id: u32) -> Result<User>;
+    }
+
+    #[derive(Default)]
+    pub struct InMemoryUserRepository {
+        users: HashMap<u32, User>,
+    }
+
+    impl InMemoryUserRepository {
+        pub fn insert(&mut self, user: User) {
+            self.users.insert(user.id, user);
         }
+    }
 
-        Ok(User {
-            id: user_id,
-            name: format!("用户{}", user_id),
-            email: format!("user{}@example.com", user_id),
-        })
+    impl UserRepository for InMemoryUserRepository {
+        fn find(&self, id: u32) -> Result<User> {
+            match self.users.get(&id) {
+                Some(user) => Ok(user.clone()),
+                None => bail!("用户{}不存在", id),
+            }
+        }
     }
 
-    fn build_profile(user: &User) -> Result<UserProfile> {
+    fn fetch_user(repo: &dyn UserRepository, user_id: u32) -> Result<User> {
+        repo.find(user_id)
+    }
+
+    pub fn build_profile(user: &User) -> Result<UserProfile> {
         if user.email.is_empty() {
             bail!("用户邮箱为空");
         }
@@ -195,18 +517,56 @@ mod use_anyhow {
         })
     }
 
-    #[derive(Debug)]
-    struct User {
-        id: u32,
-        name: String,
-        email: String,
+    #[derive(Debug, Clone)]
+    pub struct User {
+        pub id: u32,
+        pub name: String,
+        pub email: String,
     }
 
-    #[derive(Debug)]
-    struct UserProfile {
-        user_id: u32,
-        display_name: String,
-        avatar_url: String,
+    #[derive(Debug, Serialize, Deserialize)]
+    pub struct UserProfile {
+        pub user_id: u32,
+        pub display_name: String,
+        pub avatar_url: String,
+    }
+
+    #[test]
+    // cargo test --lib -F error-handling-patterns -- test_user_profile_json --nocapture
+    fn test_user_profile_json() {
+        let user = User {
+            id: 7,
+            name: "张三".to_string(),
+            email: "zhangsan@example.com".to_string(),
+        };
+        let profile = build_profile(&user).unwrap();
+
+        let json = serde_json::to_value(&profile).unwrap();
+        println!("{}", json);
+        assert_eq!(json["display_name"], "张三");
+        assert_eq!(json["avatar_url"], "https://avatar.example.com/7");
+
+        let parsed: UserProfile = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.user_id, 7);
+    }
+
+    #[test]
+    // cargo test --lib -F error-handling-patterns -- test_process_user_data --nocapture
+    fn test_process_user_data() {
+        let mut repo = InMemoryUserRepository::default();
+        repo.insert(User {
+            id: 1,
+            name: "李四".to_string(),
+            email: "lisi@example.com".to_string(),
+        });
+
+        let profile = process_user_data(&repo, 1).unwrap();
+        assert_eq!(profile.display_name, "李四");
+
+        let err = process_user_data(&repo, 999).unwrap_err();
+        println!("{:#}", err);
+        assert_eq!(err.to_string(), "获取用户999失败");
+        assert_eq!(err.root_cause().to_string(), "用户999不存在");
     }
 }
 
@@ -250,6 +610,12 @@ mod error_layout {
 
         #[error("内部服务器错误")]
         Internal(#[from] anyhow::Error),
+
+        #[error("业务错误: {0}")]
+        Coded(&'static str), // 👈 应用自定义的错误码，映射关系由ErrorCatalog提供
+
+        #[error("文件处理错误")]
+        FileSystem(#[from] super::FileError),
     }
 
     // 4. HTTP响应转换
@@ -259,33 +625,116 @@ mod error_layout {
         Json,
     };
     use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::{LazyLock, PoisonError, RwLock};
 
-    impl IntoResponse for AppError {
-        fn into_response(self) -> Response {
-            let (status, message, code) = match &self {
-                AppError::Domain(UserDomainError::NotFound { .. }) => {
-                    (StatusCode::NOT_FOUND, "用户不存在", "USER_NOT_FOUND")
-                }
-                AppError::Domain(UserDomainError::AlreadyExists { .. }) => {
-                    (StatusCode::CONFLICT, "用户已存在", "USER_ALREADY_EXISTS")
-                }
-                AppError::Domain(UserDomainError::ValidationFailed { .. }) => {
-                    (StatusCode::BAD_REQUEST, "输入验证失败", "VALIDATION_FAILED")
-                }
-                AppError::Infrastructure(_) => {
-                    // 🚨 不向用户暴露基础设施错误详情
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        "服务暂时不可用",
-                        "SERVICE_UNAVAILABLE",
-                    )
-                }
-                AppError::Internal(_) => (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "内部服务器错误",
-                    "INTERNAL_ERROR",
-                ),
-            };
+    // 错误码 -> (HTTP状态码, 对外提示信息)
+    pub struct ErrorCatalog {
+        entries: HashMap<&'static str, (StatusCode, &'static str)>,
+    }
+
+    impl ErrorCatalog {
+        // 空目录，所有错误码都按内部错误处理
+        pub fn new() -> Self {
+            Self {
+                entries: HashMap::new(),
+            }
+        }
+
+        // 预置内置错误码映射的目录
+        pub fn with_defaults() -> Self {
+            let mut catalog = Self::new();
+            catalog.register("USER_NOT_FOUND", StatusCode::NOT_FOUND, "用户不存在");
+            catalog.register("USER_ALREADY_EXISTS", StatusCode::CONFLICT, "用户已存在");
+            catalog.register("VALIDATION_FAILED", StatusCode::BAD_REQUEST, "输入验证失败");
+            // 🚨 不向用户暴露基础设施错误详情
+            catalog.register(
+                "SERVICE_UNAVAILABLE",
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "服务暂时不可用",
+            );
+            catalog.register(
+                "INTERNAL_ERROR",
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "内部服务器错误",
+            );
+            catalog.register(
+                "FILE_IO_ERROR",
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "文件处理失败",
+            );
+            catalog.register(
+                "FILE_CONTENT_INVALID",
+                StatusCode::BAD_REQUEST,
+                "文件内容无效",
+            );
+            catalog
+        }
+
+        pub fn register(&mut self, code: &'static str, status: StatusCode, message: &'static str) {
+            self.entries.insert(code, (status, message));
+        }
+
+        pub fn unregister(&mut self, code: &str) -> Option<(StatusCode, &'static str)> {
+            self.entries.remove(code)
+        }
+
+        // 未注册的错误码统一按内部错误处理
+        pub fn lookup(&self, code: &str) -> (StatusCode, &'static str) {
+            self.entries
+                .get(code)
+                .copied()
+                .unwrap_or((StatusCode::INTERNAL_SERVER_ERROR, "内部服务器错误"))
+        }
+    }
+
+    impl Default for ErrorCatalog {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    // 全局错误目录：应用启动时注册自定义映射，无需修改into_response
+    // ⚠️ 进程级全局状态：注册后对之后的所有请求（以及同一测试二进制中的所有测试）生效
+    pub static ERROR_CATALOG: LazyLock<RwLock<ErrorCatalog>> =
+        LazyLock::new(|| RwLock::new(ErrorCatalog::with_defaults()));
+
+    impl AppError {
+        pub fn code(&self) -> &'static str {
+            match self {
+                AppError::Domain(UserDomainError::NotFound { .. }) => "USER_NOT_FOUND",
+                AppError::Domain(UserDomainError::AlreadyExists { .. }) => "USER_ALREADY_EXISTS",
+                AppError::Domain(UserDomainError::ValidationFailed { .. }) => "VALIDATION_FAILED",
+                AppError::Infrastructure(_) => "SERVICE_UNAVAILABLE",
+                AppError::Internal(_) => "INTERNAL_ERROR",
+                AppError::Coded(code) => code,
+                AppError::FileSystem(e) => e.code(),
+            }
+        }
+
+        // 从全局目录查找 (状态码, 提示信息)
+        fn catalog_entry(&self) -> (StatusCode, &'static str) {
+            // 锁中毒时目录本身仍然可用，不能让每个错误响应都变成panic
+            ERROR_CATALOG
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .lookup(self.code())
+        }
+    }
+
+    // 以ErrorCatalog中的HTTP状态码为准，自定义错误码也能正确分类
+    // ⚠️ 状态码既不是4xx也不是5xx时（目录配置有误），按服务端错误处理
+    impl super::Classify for AppError {
+        fn is_client_error(&self) -> bool {
+            self.catalog_entry().0.is_client_error()
+        }
+    }
+
+    impl AppError {
+        // 使用指定的错误目录生成响应，不依赖全局状态（方便测试或按应用隔离）
+        pub fn into_response_with(self, catalog: &ErrorCatalog) -> Response {
+            let code = self.code();
+            let (status, message) = catalog.lookup(code);
 
             let body = Json(json!({
                 "error": {
@@ -297,6 +746,89 @@ mod error_layout {
             (status, body).into_response()
         }
     }
+
+    impl IntoResponse for AppError {
+        fn into_response(self) -> Response {
+            let catalog = ERROR_CATALOG.read().unwrap_or_else(PoisonError::into_inner);
+            self.into_response_with(&catalog)
+        }
+    }
+
+    #[tokio::test]
+    // cargo test --lib -F error-handling-patterns -- test_error_catalog --nocapture
+    async fn test_error_catalog() {
+        // new()和default()一致：空目录，只有with_defaults()带内置映射
+        assert_eq!(
+            ErrorCatalog::default().lookup("USER_NOT_FOUND").0,
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+        assert_eq!(
+            ErrorCatalog::with_defaults().lookup("USER_NOT_FOUND").0,
+            StatusCode::NOT_FOUND
+        );
+
+        // 在局部目录上验证自定义映射，不影响全局状态
+        let mut catalog = ErrorCatalog::with_defaults();
+        catalog.register(
+            "QUOTA_EXCEEDED",
+            StatusCode::TOO_MANY_REQUESTS,
+            "请求配额已用完",
+        );
+
+        let response = AppError::Coded("QUOTA_EXCEEDED").into_response_with(&catalog);
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["error"]["code"], "QUOTA_EXCEEDED");
+        assert_eq!(body["error"]["message"], "请求配额已用完");
+
+        // 内置映射保持不变
+        let response =
+            AppError::Domain(UserDomainError::NotFound { user_id: 1 }).into_response_with(&catalog);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // into_response读取全局ERROR_CATALOG：使用本测试专用的错误码，结束前移除
+        const CODE: &str = "TEST_ERROR_CATALOG_QUOTA_EXCEEDED";
+        let global = || {
+            ERROR_CATALOG
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+        };
+        global().register(CODE, StatusCode::TOO_MANY_REQUESTS, "请求配额已用完");
+        let status = AppError::Coded(CODE).into_response().status();
+        global().unregister(CODE);
+
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            AppError::Coded(CODE).into_response().status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    // handler中可以直接用?传播FileError
+    fn load_number(path: &str) -> Result<i32, AppError> {
+        let number = super::read_and_parse_number(path)?; // 👈 FileError -> AppError::FileSystem
+        Ok(number)
+    }
+
+    #[test]
+    // cargo test --lib -F error-handling-patterns -- test_file_error_into_app_error --nocapture
+    fn test_file_error_into_app_error() {
+        let err = load_number("不存在的文件.txt").unwrap_err();
+        assert!(matches!(err, AppError::FileSystem(super::FileError::Io(_))));
+        assert_eq!(err.code(), "FILE_IO_ERROR");
+        assert_eq!(
+            err.into_response().status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        let err = AppError::from(super::FileError::Validation("数字不能为负".to_string()));
+        assert!(super::Classify::is_client_error(&err));
+        assert_eq!(err.into_response().status(), StatusCode::BAD_REQUEST);
+    }
 }
 
 mod error_bad_practice {
@@ -348,10 +880,42 @@ mod error_bad_practice {
         Ok(())
     }
 
+    // ✅ 批量处理时不因为一个文件失败就中止，每个文件单独返回带上下文的结果
+    fn process_files_collect(paths: &[&str]) -> Vec<(String, anyhow::Result<()>)> {
+        paths
+            .iter()
+            .map(|&path| {
+                let result = std::fs::read_to_string(path)
+                    .with_context(|| format!("读取文件失败: {}", path))
+                    .and_then(|content| {
+                        process_content(&content)
+                            .with_context(|| format!("处理文件内容失败: {}", path))
+                    });
+                (path.to_string(), result)
+            })
+            .collect()
+    }
+
     fn process_content(_content: &str) -> anyhow::Result<()> {
         // 模拟处理逻辑
         Ok(())
     }
+
+    #[test]
+    // cargo test --lib -F error-handling-patterns -- test_process_files_collect --nocapture
+    fn test_process_files_collect() {
+        let existing = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        let missing = "不存在的文件.json";
+        let results = process_files_collect(&[missing, existing, missing]);
+
+        assert_eq!(results.len(), 3); // 👈 第一个失败后仍然继续处理
+        assert_eq!(results[0].0, missing);
+        assert!(results[1].1.is_ok());
+        for idx in [0, 2] {
+            let err = results[idx].1.as_ref().unwrap_err();
+            assert_eq!(err.to_string(), format!("读取文件失败: {}", missing));
+        }
+    }
 }
 
 // 库开发：使用thiserror
@@ -370,6 +934,42 @@ pub mod my_library {
     pub fn library_function() -> Result<(), LibraryError> {
         Err(LibraryError::Config("缺少必要配置".to_string()))
     }
+
+    // 库代码也能添加上下文，同时保留具体的错误类型（不必切换到anyhow）
+    #[derive(Error, Debug)]
+    #[error("{context}")]
+    pub struct WithContext<E: std::error::Error + 'static> {
+        pub context: String,
+        #[source] // 👈 原始错误通过source()暴露，调用方仍可downcast
+        pub inner: E,
+    }
+
+    pub trait ResultExt<T, E: std::error::Error + 'static> {
+        fn ctx(self, context: impl Into<String>) -> Result<T, WithContext<E>>;
+    }
+
+    impl<T, E: std::error::Error + 'static> ResultExt<T, E> for Result<T, E> {
+        fn ctx(self, context: impl Into<String>) -> Result<T, WithContext<E>> {
+            self.map_err(|inner| WithContext {
+                context: context.into(),
+                inner,
+            })
+        }
+    }
+
+    #[test]
+    // cargo test --lib -F error-handling-patterns -- test_with_context --nocapture
+    fn test_with_context() {
+        use std::error::Error as _;
+
+        let err = library_function().ctx("初始化插件失败").unwrap_err();
+        assert_eq!(err.to_string(), "初始化插件失败");
+        assert!(matches!(err.inner, LibraryError::Config(_))); // 👈 类型信息没有丢失
+
+        let source = err.source().expect("应该保留原始错误");
+        assert_eq!(source.to_string(), "配置错误: 缺少必要配置");
+        assert!(source.downcast_ref::<LibraryError>().is_some());
+    }
 }
 
 // 应用开发：使用anyhow
@@ -385,7 +985,9 @@ mod my_application {
 
 mod error_retry {
     use anyhow::Result;
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::time::Duration;
+    use tokio::time::Instant;
 
     // 简单的重试机制
     async fn with_retry<F, T>(mut operation: F, max_retries: usize) -> Result<T>
@@ -409,6 +1011,218 @@ mod error_retry {
         Err(last_error.unwrap())
     }
 
+    // 同步版本：没有tokio运行时也能用，错误类型不限于anyhow::Error
+    fn retry_blocking<F, T, E>(mut op: F, max_retries: usize, delay: Duration) -> Result<T, E>
+    where
+        F: FnMut() -> Result<T, E>,
+    {
+        let mut attempt = 0;
+        loop {
+            match op() {
+                Ok(result) => return Ok(result),
+                Err(e) if attempt >= max_retries => return Err(e), // 👈 返回最后一次的错误
+                Err(_) => {
+                    attempt += 1;
+                    std::thread::sleep(delay); // ⚠️ 会阻塞当前线程，不要在async代码中使用
+                }
+            }
+        }
+    }
+
+    #[test]
+    // cargo test --lib -F error-handling-patterns -- test_retry_blocking --nocapture
+    fn test_retry_blocking() {
+        let mut calls = 0;
+        let result = retry_blocking(
+            || {
+                calls += 1;
+                if calls < 3 {
+                    Err("连接失败")
+                } else {
+                    Ok(calls * 10)
+                }
+            },
+            3,
+            Duration::from_millis(1),
+        );
+        assert_eq!(result, Ok(30));
+        assert_eq!(calls, 3);
+
+        let result: Result<(), &str> = retry_blocking(|| Err("总是失败"), 2, Duration::ZERO);
+        assert_eq!(result, Err("总是失败"));
+    }
+
+    // 异步版本：每次重试都重新创建并等待Future
+    async fn with_retry_async<F, Fut, T>(mut operation: F, max_retries: usize) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_error = None;
+
+        for attempt in 0..=max_retries {
+            match operation().await {
+                Ok(result) => return Ok(result),
+                Err(e) => {
+                    last_error = Some(e);
+                    if attempt < max_retries {
+                        tokio::time::sleep(Duration::from_millis(100 * (attempt + 1) as u64)).await;
+                    }
+                }
+            }
+        }
+
+        Err(last_error.unwrap())
+    }
+
+    #[tokio::test]
+    // cargo test --lib -F error-handling-patterns -- test_with_retry_async --nocapture
+    async fn test_with_retry_async() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let calls = AtomicUsize::new(0);
+        let result = with_retry_async(
+            || async {
+                let attempt = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                if attempt < 3 {
+                    anyhow::bail!("第{}次请求失败", attempt)
+                }
+                Ok("数据获取成功")
+            },
+            3,
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), "数据获取成功");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    // 按截止时间重试：不限次数，到达deadline后返回最后一次的错误
+    async fn with_retry_until<F, T>(mut operation: F, deadline: Instant) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation() {
+                Ok(result) => return Ok(result),
+                Err(e) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(e);
+                    }
+                    let backoff = Duration::from_millis(100 * (attempt + 1) as u64);
+                    tokio::time::sleep_until((now + backoff).min(deadline)).await; // 👈 不会睡过截止时间
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    // cargo test --lib -F error-handling-patterns -- test_with_retry_until --nocapture
+    async fn test_with_retry_until() {
+        let start = Instant::now();
+        let deadline = start + Duration::from_millis(500);
+
+        let mut calls = 0;
+        let result: Result<()> = with_retry_until(
+            || {
+                calls += 1;
+                anyhow::bail!("第{}次请求失败", calls)
+            },
+            deadline,
+        )
+        .await;
+
+        // 重试时间点: 0ms, 100ms, 300ms, 500ms
+        assert_eq!(result.unwrap_err().to_string(), "第4次请求失败");
+        assert_eq!(calls, 4);
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+
+    // 全局错误预算：多个操作共享，每次失败消耗1，耗尽后所有操作都不再重试
+    // 👈 防止下游故障时大量重试进一步放大压力
+    struct RetryBudget {
+        remaining: AtomicUsize,
+    }
+
+    impl RetryBudget {
+        fn new(budget: usize) -> Self {
+            Self {
+                remaining: AtomicUsize::new(budget),
+            }
+        }
+
+        // 预算充足时扣减1并返回true
+        fn try_consume(&self) -> bool {
+            self.remaining
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+        }
+    }
+
+    async fn with_retry_budgeted<F, T>(
+        mut operation: F,
+        max_retries: usize,
+        budget: &RetryBudget,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let mut attempt = 0;
+        loop {
+            match operation() {
+                Ok(result) => return Ok(result),
+                Err(e) => {
+                    // ⚠️ 单次调用还有重试次数，但全局预算耗尽时同样放弃
+                    if attempt >= max_retries || !budget.try_consume() {
+                        return Err(e);
+                    }
+                    tokio::time::sleep(Duration::from_millis(100 * (attempt + 1) as u64)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    // cargo test --lib -F error-handling-patterns -- test_with_retry_budgeted --nocapture
+    async fn test_with_retry_budgeted() {
+        let budget = RetryBudget::new(3);
+
+        // 第一个操作失败2次后成功，消耗2个预算
+        let mut first_calls = 0;
+        let result = with_retry_budgeted(
+            || {
+                first_calls += 1;
+                if first_calls < 3 {
+                    anyhow::bail!("第{}次请求失败", first_calls)
+                }
+                Ok(first_calls)
+            },
+            5,
+            &budget,
+        )
+        .await;
+        assert_eq!(result.unwrap(), 3);
+
+        // 第二个操作一直失败：只剩1个预算，重试1次后放弃
+        let mut second_calls = 0;
+        let result: Result<()> = with_retry_budgeted(
+            || {
+                second_calls += 1;
+                anyhow::bail!("第{}次请求失败", second_calls)
+            },
+            5,
+            &budget,
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(second_calls, 2);
+        assert_eq!(budget.remaining.load(Ordering::SeqCst), 0);
+    }
+
     // 使用示例
     async fn fetch_data_with_retry() -> Result<String> {
         with_retry(
@@ -435,3 +1249,90 @@ fn report_error(e: &anyhow::Error) {
         eprintln!("   原因 {}: {}", i + 1, cause);
     }
 }
+
+// 以JSON结构输出错误链，方便服务直接写入结构化日志
+fn error_to_json(e: &anyhow::Error) -> serde_json::Value {
+    let causes: Vec<String> = e.chain().skip(1).map(|cause| cause.to_string()).collect();
+    serde_json::json!({
+        "error": e.to_string(),
+        "causes": causes,
+    })
+}
+
+#[test]
+// cargo test --lib -F error-handling-patterns -- test_error_to_json --nocapture
+fn test_error_to_json() {
+    use anyhow::Context;
+
+    let e = Err::<(), _>(io::Error::new(io::ErrorKind::NotFound, "文件不存在"))
+        .context("读取配置失败")
+        .context("启动服务失败")
+        .unwrap_err();
+
+    let value = error_to_json(&e);
+    println!("{}", value);
+    assert_eq!(value["error"], "启动服务失败");
+
+    let causes = value["causes"].as_array().unwrap();
+    let expected: Vec<String> = e.chain().skip(1).map(|c| c.to_string()).collect();
+    assert_eq!(causes.len(), 2);
+    assert_eq!(causes, &expected);
+}
+
+// 防御性地遍历错误链：最多输出max_depth层，并检测循环引用
+// ⚠️ report_error等遍历方式假设source链无环，恶意构造的错误可能导致死循环
+fn print_chain_safe(e: &(dyn Error + 'static), max_depth: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let mut current = Some(e);
+    while let Some(err) = current {
+        if lines.len() >= max_depth {
+            break; // 👈 超过最大深度，截断
+        }
+        // 用胖指针（地址 + vtable）判断是否访问过同一个错误对象
+        // ⚠️ 只比较地址不够：newtype错误的source()可能就是偏移为0的字段，与自身地址相同
+        if !seen.insert(err as *const dyn Error) {
+            break; // 👈 检测到环
+        }
+        lines.push(err.to_string());
+        current = err.source();
+    }
+    lines
+}
+
+#[test]
+// cargo test --lib -F error-handling-patterns -- test_print_chain_safe --nocapture
+fn test_print_chain_safe() {
+    let mut err: Box<dyn Error> = Box::new(io::Error::other("根因"));
+    for i in 0..20 {
+        err = Box::new(MyError(format!("第{}层", i), err));
+    }
+
+    let lines = print_chain_safe(&*err, 5);
+    println!("{:#?}", lines);
+    assert_eq!(lines.len(), 5);
+    assert_eq!(lines[0], "MyError occurred: 第19层");
+    assert_eq!(lines[4], "MyError occurred: 第15层");
+
+    // 深度足够时完整输出，包括最底层的根因
+    let lines = print_chain_safe(&*err, 100);
+    assert_eq!(lines.len(), 21);
+    assert_eq!(lines.last().unwrap(), "根因");
+
+    // newtype包装：source()指向的字段与包装器地址相同，不能误判为环
+    #[derive(Debug)]
+    struct Wrap(io::Error);
+    impl std::fmt::Display for Wrap {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "wrap")
+        }
+    }
+    impl Error for Wrap {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    let wrapped = Wrap(io::Error::other("root"));
+    assert_eq!(print_chain_safe(&wrapped, 10), vec!["wrap", "root"]);
+}
@@ -1,3 +1,4 @@
+#[cfg(feature = "std")]
 mod walk_iter {
     // ❌ 错误：使用索引操作
     fn use_index_walk_iter(vec: &[i32]) {
@@ -35,7 +36,17 @@ mod walk_iter {
     }
 }
 
+// ✨ 只依赖core/alloc中的迭代器trait，关闭std特性也能编译（no_std）
+// cargo +nightly build --lib --no-default-features -F iterator-patterns（CI中的no-std任务）
 mod impl_my_iter_ext {
+    use alloc::vec::Vec;
+    use core::cmp::Ordering;
+    #[cfg(feature = "std")]
+    use core::hash::Hash;
+    use core::ops::ControlFlow;
+    #[cfg(feature = "std")]
+    use std::collections::{HashMap, HashSet};
+
     // 不可变引用迭代器
     pub struct Iter<'a, T> {
         slice: &'a [T],
@@ -61,12 +72,54 @@ mod impl_my_iter_ext {
         }
     }
 
+    impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+    // 从尾部取元素：直接缩短切片，idx不受影响
+    impl<T> DoubleEndedIterator for Iter<'_, T> {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            if self.idx == self.slice.len() {
+                return None;
+            }
+            let (last, rest) = self.slice.split_last()?;
+            self.slice = rest;
+            Some(last)
+        }
+    }
+
+    impl<'a, T> Iter<'a, T> {
+        // 在剩余元素的mid处切分为两个独立的迭代器，mid超出长度时取剩余长度
+        pub fn split_at(self, mid: usize) -> (Iter<'a, T>, Iter<'a, T>) {
+            let rest = &self.slice[self.idx..];
+            let (front, back) = rest.split_at(mid.min(rest.len()));
+            (Iter::from(front), Iter::from(back))
+        }
+    }
+
+    // 手动实现Clone：只复制切片引用，不要求T: Clone
+    impl<T> Clone for Iter<'_, T> {
+        fn clone(&self) -> Self {
+            Self {
+                slice: self.slice,
+                idx: self.idx,
+            }
+        }
+    }
+
     impl<'a, T> From<&'a [T]> for Iter<'a, T> {
         fn from(slice: &'a [T]) -> Self {
             Self { slice, idx: 0 }
         }
     }
 
+    impl<'a, T, const N: usize> From<&'a [T; N]> for Iter<'a, T> {
+        fn from(array: &'a [T; N]) -> Self {
+            Self {
+                slice: array,
+                idx: 0,
+            }
+        }
+    }
+
     // 迭代器功能拓展
     pub trait MyIter: Iterator {
         fn my_map<F, R>(self, f: F) -> Map<Self, F>
@@ -84,6 +137,361 @@ mod impl_my_iter_ext {
         {
             Filter { iter: self, f }
         }
+
+        // 只保留返回Some的结果，相当于my_map + my_filter一步完成
+        fn my_filter_map<B, F>(self, f: F) -> FilterMap<Self, F>
+        where
+            Self: Sized,
+            F: Fn(Self::Item) -> Option<B>,
+        {
+            FilterMap { iter: self, f }
+        }
+
+        // 把引用迭代器转为值迭代器
+        fn my_cloned<'a, T>(self) -> Cloned<Self>
+        where
+            Self: Sized + Iterator<Item = &'a T>,
+            T: 'a + Clone,
+        {
+            Cloned { iter: self }
+        }
+
+        // 与my_cloned类似，但只要求Copy，对基本类型没有clone开销
+        fn my_copied<'a, T>(self) -> Copied<Self>
+        where
+            Self: Sized + Iterator<Item = &'a T>,
+            T: 'a + Copy,
+        {
+            Copied { iter: self }
+        }
+
+        fn my_take(self, n: usize) -> Take<Self>
+        where
+            Self: Sized,
+        {
+            Take { iter: self, n }
+        }
+
+        // 只转换Result中的错误，Ok原样传递
+        fn my_map_err<T, E, G, F>(self, f: F) -> MapErr<Self, F>
+        where
+            Self: Sized + Iterator<Item = Result<T, E>>,
+            F: Fn(E) -> G,
+        {
+            MapErr { iter: self, f }
+        }
+
+        // 从尾部开始分块，长度不足的余数块最后返回（与std的rchunks一致）
+        fn my_rchunks(self, size: usize) -> RChunks<Self>
+        where
+            Self: Sized + ExactSizeIterator,
+        {
+            assert!(size != 0, "chunk size must be non-zero");
+            RChunks {
+                iter: Some(self),
+                size,
+                chunks: Vec::new(),
+            }
+        }
+
+        // 借用而不是消费迭代器，适配器用完后还能继续使用原迭代器
+        fn my_by_ref(&mut self) -> &mut Self
+        where
+            Self: Sized,
+        {
+            self
+        }
+
+        // 跳过n个元素后返回下一个，只消费需要的元素，之后还能继续迭代
+        fn my_nth(&mut self, n: usize) -> Option<Self::Item> {
+            for _ in 0..n {
+                self.next()?; // 👈 元素不足时提前返回None
+            }
+            self.next()
+        }
+
+        // 从尾部开始查找，返回的下标仍然从头部开始计数（与std的rposition一致）
+        fn my_rposition<F>(&mut self, mut pred: F) -> Option<usize>
+        where
+            Self: Sized + DoubleEndedIterator + ExactSizeIterator,
+            F: FnMut(Self::Item) -> bool,
+        {
+            let mut i = self.len();
+            while let Some(item) = self.next_back() {
+                i -= 1; // 👈 每从尾部取一个，下标减一
+                if pred(item) {
+                    return Some(i);
+                }
+            }
+            None
+        }
+
+        // 按投影的key取最大值，key相同时返回最早出现的元素
+        fn my_max_by_key<B, F>(self, mut f: F) -> Option<Self::Item>
+        where
+            Self: Sized,
+            B: Ord,
+            F: FnMut(&Self::Item) -> B,
+        {
+            let mut best: Option<(B, Self::Item)> = None;
+            for item in self {
+                let key = f(&item);
+                if best.as_ref().is_none_or(|(best_key, _)| key > *best_key) {
+                    best = Some((key, item));
+                }
+            }
+            best.map(|(_, item)| item)
+        }
+
+        // 按投影的key取最小值，key相同时返回最早出现的元素
+        fn my_min_by_key<B, F>(self, mut f: F) -> Option<Self::Item>
+        where
+            Self: Sized,
+            B: Ord,
+            F: FnMut(&Self::Item) -> B,
+        {
+            let mut best: Option<(B, Self::Item)> = None;
+            for item in self {
+                let key = f(&item);
+                if best.as_ref().is_none_or(|(best_key, _)| key < *best_key) {
+                    best = Some((key, item));
+                }
+            }
+            best.map(|(_, item)| item)
+        }
+
+        // 按自定义比较函数取最大值，与std的max_by一致：相等时返回最后一个
+        fn my_max_by<F>(self, mut compare: F) -> Option<Self::Item>
+        where
+            Self: Sized,
+            F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+        {
+            self.reduce(|best, item| match compare(&best, &item) {
+                Ordering::Greater => best,
+                Ordering::Less | Ordering::Equal => item, // 👈 相等时后来者胜出
+            })
+        }
+
+        // 按自定义比较函数取最小值，与std的min_by一致：相等时返回第一个
+        fn my_min_by<F>(self, mut compare: F) -> Option<Self::Item>
+        where
+            Self: Sized,
+            F: FnMut(&Self::Item, &Self::Item) -> Ordering,
+        {
+            self.reduce(|best, item| match compare(&best, &item) {
+                Ordering::Greater => item,
+                Ordering::Less | Ordering::Equal => best,
+            })
+        }
+
+        // 收集后按key排序，sort_by_key是稳定排序：key相同的元素保持原有顺序
+        fn my_sorted_by_key<K, F>(self, key_fn: F) -> Vec<Self::Item>
+        where
+            Self: Sized,
+            K: Ord,
+            F: FnMut(&Self::Item) -> K,
+        {
+            let mut items: Vec<_> = self.collect();
+            items.sort_by_key(key_fn);
+            items
+        }
+
+        // 带缓存的map：相同的输入只计算一次，适合昂贵的纯函数
+        #[cfg(feature = "std")] // 👈 HashMap需要std
+        fn my_map_cached<R, F>(self, f: F) -> MapCached<Self, F, R>
+        where
+            Self: Sized,
+            Self::Item: Eq + Hash + Clone,
+            F: Fn(Self::Item) -> R,
+            R: Clone,
+        {
+            MapCached {
+                iter: self,
+                f,
+                cache: HashMap::new(),
+            }
+        }
+
+        // 按key_fn分组收集（全局分组，不要求相同key的元素相邻）
+        #[cfg(feature = "std")]
+        fn my_chunk_by_key<K, F>(self, mut key_fn: F) -> HashMap<K, Vec<Self::Item>>
+        where
+            Self: Sized,
+            K: Eq + Hash,
+            F: FnMut(&Self::Item) -> K,
+        {
+            let mut groups: HashMap<K, Vec<Self::Item>> = HashMap::new();
+            for item in self {
+                groups.entry(key_fn(&item)).or_default().push(item);
+            }
+            groups
+        }
+
+        // 交集：保留两边都出现的元素，去重，按self中首次出现的顺序
+        #[cfg(feature = "std")]
+        fn my_intersect<I>(self, other: I) -> Vec<Self::Item>
+        where
+            Self: Sized,
+            Self::Item: Eq + Hash + Clone,
+            I: IntoIterator<Item = Self::Item>,
+        {
+            let other: HashSet<Self::Item> = other.into_iter().collect();
+            let mut seen = HashSet::new();
+            self.filter(|item| other.contains(item) && seen.insert(item.clone()))
+                .collect()
+        }
+
+        // 并集：先self后other，去重并保留首次出现的顺序
+        #[cfg(feature = "std")]
+        fn my_union<I>(self, other: I) -> Vec<Self::Item>
+        where
+            Self: Sized,
+            Self::Item: Eq + Hash + Clone,
+            I: IntoIterator<Item = Self::Item>,
+        {
+            let mut seen = HashSet::new();
+            self.chain(other)
+                .filter(|item| seen.insert(item.clone()))
+                .collect()
+        }
+
+        // 无限重复序列：耗尽后从原始迭代器的克隆重新开始
+        fn my_cycle(self) -> Cycle<Self>
+        where
+            Self: Sized + Clone,
+        {
+            Cycle {
+                orig: self.clone(),
+                iter: self,
+            }
+        }
+
+        // 按分隔符切分为多组，分隔符本身被丢弃，连续分隔符之间保留空组
+        fn my_split<F>(self, is_delim: F) -> Split<Self, F>
+        where
+            Self: Sized,
+            F: Fn(&Self::Item) -> bool,
+        {
+            Split {
+                iter: self,
+                is_delim,
+                after_delim: false,
+            }
+        }
+
+        // 相邻元素两两配对：(prev, cur)，相当于返回元组的2元素窗口
+        fn my_pairwise(self) -> Pairwise<Self>
+        where
+            Self: Sized,
+            Self::Item: Clone,
+        {
+            Pairwise {
+                iter: self,
+                prev: None,
+            }
+        }
+
+        // 展开嵌套的迭代器，空的内层迭代器会被跳过
+        fn my_flatten(self) -> Flatten<Self>
+        where
+            Self: Sized,
+            Self::Item: IntoIterator,
+        {
+            Flatten {
+                iter: self,
+                inner: None,
+            }
+        }
+
+        // 带状态的map：f返回None时提前结束（与std的scan一致）
+        fn my_scan<St, B, F>(self, initial_state: St, f: F) -> Scan<Self, St, F>
+        where
+            Self: Sized,
+            F: FnMut(&mut St, Self::Item) -> Option<B>,
+        {
+            Scan {
+                iter: self,
+                state: initial_state,
+                f,
+            }
+        }
+
+        // 对每个元素执行有副作用的操作，遇到第一个Err立即停止
+        fn my_try_for_each<E, F>(self, mut f: F) -> Result<(), E>
+        where
+            Self: Sized,
+            F: FnMut(Self::Item) -> Result<(), E>,
+        {
+            for item in self {
+                f(item)?; // 👈 后面的元素不会再被拉取
+            }
+            Ok(())
+        }
+
+        // collect::<Result<Vec<_>, _>>() 的简写，遇到第一个Err立即返回
+        fn my_collect_result_vec<V, E>(self) -> Result<Vec<V>, E>
+        where
+            Self: Sized + Iterator<Item = Result<V, E>>,
+        {
+            let mut vec = Vec::with_capacity(self.size_hint().0);
+            for item in self {
+                vec.push(item?);
+            }
+            Ok(vec)
+        }
+
+        // 折叠直到f返回Break，Break和Continue都携带当前的累加值
+        fn my_fold_while<B, F>(self, init: B, mut f: F) -> B
+        where
+            Self: Sized,
+            F: FnMut(B, Self::Item) -> ControlFlow<B, B>,
+        {
+            let mut acc = init;
+            for item in self {
+                match f(acc, item) {
+                    ControlFlow::Continue(next) => acc = next,
+                    ControlFlow::Break(done) => return done, // 👈 提前结束，不再拉取元素
+                }
+            }
+            acc
+        }
+
+        // 一次遍历同时求和与平均值，空迭代器返回(0, 0.0)
+        fn my_sum_avg(self) -> (i64, f64)
+        where
+            Self: Sized,
+            Self::Item: Into<i64>,
+        {
+            let (sum, count) = self.fold((0i64, 0usize), |(sum, count), x| {
+                (sum + x.into(), count + 1)
+            });
+            if count == 0 {
+                return (0, 0.0); // 👈 避免除以0得到NaN
+            }
+            (sum, sum as f64 / count as f64)
+        }
+
+        // 前缀和：[1, 2, 3, 4] -> [1, 3, 6, 10]
+        fn my_prefix_sums(self) -> Vec<i64>
+        where
+            Self: Sized,
+            Self::Item: Into<i64>,
+        {
+            self.my_scan(0i64, |acc, x| {
+                *acc += x.into();
+                Some(*acc)
+            })
+            .collect()
+        }
+
+        // 包装成futures::Stream：每次poll都立即就绪，方便接入异步管道
+        #[cfg(feature = "async")]
+        fn into_stream(self) -> impl futures::Stream<Item = Self::Item>
+        where
+            Self: Sized,
+        {
+            futures::stream::iter(self)
+        }
     }
 
     // 自定义迭代器器适配
@@ -93,47 +501,393 @@ mod impl_my_iter_ext {
 
     impl<I, F> MyIter for Filter<I, F> where Self: Iterator {}
 
+    impl<I, F> MyIter for FilterMap<I, F> where Self: Iterator {}
+
+    impl<I> MyIter for Cloned<I> where Self: Iterator {}
+
+    impl<I> MyIter for Copied<I> where Self: Iterator {}
+
+    // &mut I 同样实现了Iterator，my_by_ref返回的引用也能继续链式调用
+    impl<I: Iterator + ?Sized> MyIter for &mut I {}
+
+    impl<I> MyIter for Take<I> where Self: Iterator {}
+
+    #[cfg(feature = "std")]
+    impl<I: Iterator, F, R> MyIter for MapCached<I, F, R> where Self: Iterator {}
+
+    impl<I: ExactSizeIterator> MyIter for RChunks<I> {}
+
+    impl<I> MyIter for Cycle<I> where Self: Iterator {}
+
+    impl<I, F> MyIter for MapErr<I, F> where Self: Iterator {}
+
+    impl<I, F> MyIter for Split<I, F> where Self: Iterator {}
+
+    impl<I: Iterator> MyIter for Pairwise<I> where Self: Iterator {}
+
+    impl<I, St, F> MyIter for Scan<I, St, F> where Self: Iterator {}
+
+    impl<I> MyIter for Flatten<I>
+    where
+        I: Iterator,
+        I::Item: IntoIterator,
+    {
+    }
+
     // map 迭代器
+    #[derive(Clone)]
     pub struct Map<I, F> {
         iter: I,
         f: F,
     }
 
-    impl<I, F, T> Iterator for Map<I, F>
+    impl<I, F, T> Iterator for Map<I, F>
+    where
+        I: Iterator,
+        F: Fn(I::Item) -> T,
+    {
+        type Item = T;
+        fn next(&mut self) -> Option<Self::Item> {
+            if let Some(item) = self.iter.next() {
+                Some((self.f)(item))
+            } else {
+                None
+            }
+        }
+    }
+
+    // filter 迭代器
+    #[derive(Clone)]
+    pub struct Filter<I, F> {
+        iter: I,
+        f: F,
+    }
+
+    impl<I, F> Iterator for Filter<I, F>
+    where
+        I: Iterator,
+        F: Fn(&I::Item) -> bool,
+    {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            for item in &mut self.iter {
+                if (self.f)(&item) {
+                    return Some(item);
+                }
+            }
+            None
+        }
+    }
+
+    // map_cached 迭代器
+    #[cfg(feature = "std")]
+    pub struct MapCached<I: Iterator, F, R> {
+        iter: I,
+        f: F,
+        cache: HashMap<I::Item, R>, // 👈 输入 -> 计算结果
+    }
+
+    #[cfg(feature = "std")]
+    impl<I, F, R> Iterator for MapCached<I, F, R>
+    where
+        I: Iterator,
+        I::Item: Eq + Hash + Clone,
+        F: Fn(I::Item) -> R,
+        R: Clone,
+    {
+        type Item = R;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let item = self.iter.next()?;
+            let f = &self.f;
+            let result = self.cache.entry(item.clone()).or_insert_with(|| f(item));
+            Some(result.clone())
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.iter.size_hint()
+        }
+    }
+
+    // filter_map 迭代器
+    #[derive(Clone)]
+    pub struct FilterMap<I, F> {
+        iter: I,
+        f: F,
+    }
+
+    impl<I, F, B> Iterator for FilterMap<I, F>
+    where
+        I: Iterator,
+        F: Fn(I::Item) -> Option<B>,
+    {
+        type Item = B;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            for item in &mut self.iter {
+                if let Some(mapped) = (self.f)(item) {
+                    return Some(mapped);
+                }
+            }
+            None
+        }
+    }
+
+    // cloned 迭代器
+    #[derive(Clone)]
+    pub struct Cloned<I> {
+        iter: I,
+    }
+
+    impl<'a, I, T> Iterator for Cloned<I>
+    where
+        I: Iterator<Item = &'a T>,
+        T: 'a + Clone,
+    {
+        type Item = T;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.iter.next().cloned()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.iter.size_hint()
+        }
+    }
+
+    impl<'a, I, T> ExactSizeIterator for Cloned<I>
+    where
+        I: ExactSizeIterator<Item = &'a T>,
+        T: 'a + Clone,
+    {
+    }
+
+    // copied 迭代器
+    #[derive(Clone)]
+    pub struct Copied<I> {
+        iter: I,
+    }
+
+    impl<'a, I, T> Iterator for Copied<I>
+    where
+        I: Iterator<Item = &'a T>,
+        T: 'a + Copy,
+    {
+        type Item = T;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.iter.next().copied()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.iter.size_hint()
+        }
+    }
+
+    impl<'a, I, T> ExactSizeIterator for Copied<I>
+    where
+        I: ExactSizeIterator<Item = &'a T>,
+        T: 'a + Copy,
+    {
+    }
+
+    // take 迭代器
+    #[derive(Clone)]
+    pub struct Take<I> {
+        iter: I,
+        n: usize,
+    }
+
+    impl<I: Iterator> Iterator for Take<I> {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.n == 0 {
+                return None;
+            }
+            self.n -= 1;
+            self.iter.next()
+        }
+    }
+
+    // rchunks 迭代器
+    pub struct RChunks<I: Iterator> {
+        iter: Option<I>,
+        size: usize,
+        chunks: Vec<Vec<I::Item>>,
+    }
+
+    impl<I: ExactSizeIterator> Iterator for RChunks<I> {
+        type Item = Vec<I::Item>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            // 第一次调用时按长度从前往后切分，余数块在最前面，之后从后往前弹出
+            if let Some(mut iter) = self.iter.take() {
+                let remainder = iter.len() % self.size;
+                if remainder > 0 {
+                    self.chunks.push(iter.by_ref().take(remainder).collect());
+                }
+                while iter.len() > 0 {
+                    self.chunks.push(iter.by_ref().take(self.size).collect());
+                }
+            }
+            self.chunks.pop()
+        }
+    }
+
+    // cycle 迭代器
+    #[derive(Clone)]
+    pub struct Cycle<I> {
+        orig: I,
+        iter: I,
+    }
+
+    impl<I: Iterator + Clone> Iterator for Cycle<I> {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            match self.iter.next() {
+                Some(item) => Some(item),
+                None => {
+                    self.iter = self.orig.clone();
+                    self.iter.next() // 👈 空迭代器重新开始后仍为None，不会陷入死循环
+                }
+            }
+        }
+    }
+
+    // map_err 迭代器
+    #[derive(Clone)]
+    pub struct MapErr<I, F> {
+        iter: I,
+        f: F,
+    }
+
+    impl<I, F, T, E, G> Iterator for MapErr<I, F>
+    where
+        I: Iterator<Item = Result<T, E>>,
+        F: Fn(E) -> G,
+    {
+        type Item = Result<T, G>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            self.iter.next().map(|item| item.map_err(&self.f))
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            self.iter.size_hint()
+        }
+    }
+
+    // split 迭代器
+    #[derive(Clone)]
+    pub struct Split<I, F> {
+        iter: I,
+        is_delim: F,
+        after_delim: bool, // 👈 上一组是否以分隔符结尾，决定结尾是否还要返回一个空组
+    }
+
+    impl<I, F> Iterator for Split<I, F>
+    where
+        I: Iterator,
+        F: Fn(&I::Item) -> bool,
+    {
+        type Item = Vec<I::Item>;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let mut group = Vec::new();
+            for item in self.iter.by_ref() {
+                if (self.is_delim)(&item) {
+                    self.after_delim = true;
+                    return Some(group);
+                }
+                group.push(item);
+            }
+            // 迭代器耗尽：返回最后一组；空输入或已返回过结尾时为None
+            if group.is_empty() && !self.after_delim {
+                return None;
+            }
+            self.after_delim = false;
+            Some(group)
+        }
+    }
+
+    // pairwise 迭代器
+    #[derive(Clone)]
+    pub struct Pairwise<I: Iterator> {
+        iter: I,
+        prev: Option<I::Item>, // 👈 上一个元素，每次产出时clone一份留给下一对
+    }
+
+    impl<I> Iterator for Pairwise<I>
+    where
+        I: Iterator,
+        I::Item: Clone,
+    {
+        type Item = (I::Item, I::Item);
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let prev = match self.prev.take() {
+                Some(prev) => prev,
+                None => self.iter.next()?, // 👈 只在第一次调用时发生
+            };
+            let cur = self.iter.next()?;
+            self.prev = Some(cur.clone());
+            Some((prev, cur))
+        }
+    }
+
+    // scan 迭代器
+    #[derive(Clone)]
+    pub struct Scan<I, St, F> {
+        iter: I,
+        state: St,
+        f: F,
+    }
+
+    impl<I, St, F, B> Iterator for Scan<I, St, F>
     where
         I: Iterator,
-        F: Fn(I::Item) -> T,
+        F: FnMut(&mut St, I::Item) -> Option<B>,
     {
-        type Item = T;
+        type Item = B;
+
         fn next(&mut self) -> Option<Self::Item> {
-            if let Some(item) = self.iter.next() {
-                Some((self.f)(item))
-            } else {
-                None
-            }
+            let item = self.iter.next()?;
+            (self.f)(&mut self.state, item)
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (0, self.iter.size_hint().1) // 👈 f可能提前返回None
         }
     }
 
-    // filter 迭代器
-    pub struct Filter<I, F> {
+    // flatten 迭代器
+    pub struct Flatten<I>
+    where
+        I: Iterator,
+        I::Item: IntoIterator,
+    {
         iter: I,
-        f: F,
+        inner: Option<<I::Item as IntoIterator>::IntoIter>, // 👈 当前正在展开的内层迭代器
     }
 
-    impl<I, F> Iterator for Filter<I, F>
+    impl<I> Iterator for Flatten<I>
     where
         I: Iterator,
-        F: Fn(&I::Item) -> bool,
+        I::Item: IntoIterator,
     {
-        type Item = I::Item;
+        type Item = <I::Item as IntoIterator>::Item;
 
         fn next(&mut self) -> Option<Self::Item> {
-            for item in &mut self.iter {
-                if (self.f)(&item) {
+            loop {
+                if let Some(item) = self.inner.as_mut().and_then(Iterator::next) {
                     return Some(item);
                 }
+                // 内层耗尽（或还没开始），取下一个内层迭代器
+                self.inner = Some(self.iter.next()?.into_iter());
             }
-            None
         }
     }
 
@@ -151,6 +905,330 @@ mod impl_my_iter_ext {
         let res: Vec<_> = iter.collect();
         println!("res: {:?}", res);
     }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_filter_map --nocapture
+    fn test_iter_filter_map() {
+        let res: Vec<i32> = Iter::from(&["1", "x", "3"])
+            .my_filter_map(|s| s.parse::<i32>().ok())
+            .collect();
+        assert_eq!(res, vec![1, 3]);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_cloned --nocapture
+    fn test_iter_cloned() {
+        let res: Vec<i32> = Iter::from(&[1, 2, 3])
+            .my_cloned()
+            .my_map(|x| x * 2)
+            .collect();
+        assert_eq!(res, vec![2, 4, 6]);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_copied --nocapture
+    fn test_iter_copied() {
+        let res: Vec<i32> = Iter::from(&[1, 2, 3]).my_copied().collect();
+        assert_eq!(res, vec![1, 2, 3]);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_cycle --nocapture
+    fn test_iter_cycle() {
+        let res: Vec<_> = Iter::from(&[1, 2]).my_cycle().my_take(5).collect();
+        assert_eq!(res, vec![&1, &2, &1, &2, &1]);
+
+        let empty: [i32; 0] = [];
+        assert_eq!(Iter::from(&empty).my_cycle().next(), None);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_by_ref --nocapture
+    fn test_iter_by_ref() {
+        let mut iter = Iter::from(&[1, 2, 3, 4, 5]);
+
+        let head: Vec<_> = iter.my_by_ref().my_take(2).collect();
+        assert_eq!(head, vec![&1, &2]);
+
+        let rest: Vec<_> = iter.collect(); // 👈 原迭代器仍然可用
+        assert_eq!(rest, vec![&3, &4, &5]);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_rchunks --nocapture
+    fn test_iter_rchunks() {
+        let res: Vec<Vec<_>> = Iter::from(&[1, 2, 3, 4, 5])
+            .my_copied()
+            .my_rchunks(2)
+            .collect();
+        assert_eq!(res, vec![vec![4, 5], vec![2, 3], vec![1]]);
+
+        let res: Vec<Vec<_>> = Iter::from(&[1, 2, 3, 4]).my_rchunks(2).collect();
+        assert_eq!(res, vec![vec![&3, &4], vec![&1, &2]]);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_split_at --nocapture
+    fn test_iter_split_at() {
+        let (front, back) = Iter::from(&[1, 2, 3, 4, 5]).split_at(2);
+        assert_eq!(front.my_copied().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(back.my_copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+
+        let (front, back) = Iter::from(&[1, 2]).split_at(10);
+        assert_eq!(front.len(), 2);
+        assert_eq!(back.len(), 0);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_max_min_by_key --nocapture
+    fn test_iter_max_min_by_key() {
+        let words = ["apple", "banana", "cherry", "fig", "kiwi"];
+        // banana和cherry一样长，取先出现的
+        assert_eq!(
+            Iter::from(&words).my_max_by_key(|s| s.len()),
+            Some(&"banana")
+        );
+        assert_eq!(Iter::from(&words).my_min_by_key(|s| s.len()), Some(&"fig"));
+
+        let empty: [&str; 0] = [];
+        assert_eq!(Iter::from(&empty).my_max_by_key(|s| s.len()), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    // cargo test --lib -F iterator-patterns -- test_iter_chunk_by_key --nocapture
+    fn test_iter_chunk_by_key() {
+        let groups = Iter::from(&[1, 2, 3, 4, 5, 6, 7])
+            .my_copied()
+            .my_chunk_by_key(|n| n % 2 == 0);
+        assert_eq!(groups[&true], vec![2, 4, 6]);
+        assert_eq!(groups[&false], vec![1, 3, 5, 7]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    // cargo test --lib -F iterator-patterns -- test_iter_map_cached --nocapture
+    fn test_iter_map_cached() {
+        use std::cell::Cell;
+
+        let calls = Cell::new(0);
+        let res: Vec<i32> = Iter::from(&[1, 1, 2, 2, 1])
+            .my_copied()
+            .my_map_cached(|x| {
+                calls.set(calls.get() + 1);
+                x * 10
+            })
+            .collect();
+
+        assert_eq!(res, vec![10, 10, 20, 20, 10]);
+        assert_eq!(calls.get(), 2); // 👈 每个不同的输入只计算一次
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_split --nocapture
+    fn test_iter_split() {
+        let res: Vec<Vec<i32>> = Iter::from(&[1, 0, 2, 3, 0, 4])
+            .my_copied()
+            .my_split(|&x| x == 0)
+            .collect();
+        assert_eq!(res, vec![vec![1], vec![2, 3], vec![4]]);
+
+        // 连续分隔符之间、以及结尾分隔符之后都保留空组
+        let res: Vec<Vec<i32>> = Iter::from(&[1, 0, 0, 2, 0])
+            .my_copied()
+            .my_split(|&x| x == 0)
+            .collect();
+        assert_eq!(res, vec![vec![1], vec![], vec![2], vec![]]);
+
+        let empty: [i32; 0] = [];
+        assert_eq!(Iter::from(&empty).my_split(|&&x| x == 0).count(), 0);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_pairwise --nocapture
+    fn test_iter_pairwise() {
+        let res: Vec<(i32, i32)> = Iter::from(&[1, 2, 3]).my_copied().my_pairwise().collect();
+        assert_eq!(res, vec![(1, 2), (2, 3)]);
+
+        assert_eq!(Iter::from(&[1]).my_pairwise().count(), 0);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_prefix_sums --nocapture
+    fn test_iter_prefix_sums() {
+        // Iter产出&i32，先copied成i32才满足Into<i64>
+        let sums = Iter::from(&[1, 2, 3, 4]).my_copied().my_prefix_sums();
+        assert_eq!(sums, vec![1, 3, 6, 10]);
+
+        let empty: [i32; 0] = [];
+        assert_eq!(
+            Iter::from(&empty).my_copied().my_prefix_sums(),
+            Vec::<i64>::new()
+        );
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_try_for_each --nocapture
+    fn test_iter_try_for_each() {
+        let mut parsed = Vec::new();
+        let res = Iter::from(&["1", "x", "3"]).my_try_for_each(|s| {
+            parsed.push(s.parse::<i32>()?);
+            Ok::<_, std::num::ParseIntError>(())
+        });
+
+        assert!(res.is_err());
+        assert_eq!(parsed, vec![1]); // 👈 "3"没有被处理
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    // cargo test --lib -F iterator-patterns -- test_iter_intersect_union --nocapture
+    fn test_iter_intersect_union() {
+        let a = [1, 2, 3];
+        let b = [2, 3, 4];
+
+        let common = Iter::from(&a).my_copied().my_intersect(b);
+        assert_eq!(common, vec![2, 3]);
+
+        let all = Iter::from(&a).my_copied().my_union(b);
+        assert_eq!(all, vec![1, 2, 3, 4]);
+
+        // 重复元素只保留一次
+        let dup = Iter::from(&[2, 2, 3]).my_copied().my_intersect([3, 2, 2]);
+        assert_eq!(dup, vec![2, 3]);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_fold_while --nocapture
+    fn test_iter_fold_while() {
+        let data: Vec<i32> = (1..=100).collect();
+        let mut pulled = 0;
+
+        // 累加到超过100之前停止，返回未超限的和
+        let sum = Iter::from(data.as_slice()).my_fold_while(0, |acc, &x| {
+            pulled += 1;
+            if acc + x > 100 {
+                ControlFlow::Break(acc)
+            } else {
+                ControlFlow::Continue(acc + x)
+            }
+        });
+
+        assert_eq!(sum, 91); // 1 + 2 + ... + 13
+        assert_eq!(pulled, 14);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_nth --nocapture
+    fn test_iter_nth() {
+        let mut iter = Iter::from(&[10, 20, 30, 40]);
+        assert_eq!(iter.my_nth(1), Some(&20));
+        assert_eq!(iter.next(), Some(&30)); // 👈 从第n+1个元素之后继续
+        assert_eq!(iter.my_nth(5), None);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_flatten --nocapture
+    fn test_iter_flatten() {
+        let nested = [vec![1, 2], vec![], vec![3], vec![4, 5]];
+        let res: Vec<i32> = Iter::from(&nested).my_flatten().my_copied().collect();
+        assert_eq!(res, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_sum_avg --nocapture
+    fn test_iter_sum_avg() {
+        assert_eq!(Iter::from(&[2, 4, 6]).my_copied().my_sum_avg(), (12, 4.0));
+
+        let empty: [i32; 0] = [];
+        assert_eq!(Iter::from(&empty).my_copied().my_sum_avg(), (0, 0.0));
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_max_min_by --nocapture
+    fn test_iter_max_min_by() {
+        // f64没有实现Ord，只能用partial_cmp比较
+        let data = [2.5, -1.0, 9.75, 3.0];
+        let cmp = |a: &&f64, b: &&f64| a.partial_cmp(b).unwrap();
+        assert_eq!(Iter::from(&data).my_max_by(cmp), Some(&9.75));
+        assert_eq!(Iter::from(&data).my_min_by(cmp), Some(&-1.0));
+
+        // 相等时：max取最后一个，min取第一个
+        let pairs = [(1, 'a'), (3, 'b'), (1, 'c'), (3, 'd')];
+        let by_num = |a: &&(i32, char), b: &&(i32, char)| a.0.cmp(&b.0);
+        assert_eq!(Iter::from(&pairs).my_max_by(by_num), Some(&(3, 'd')));
+        assert_eq!(Iter::from(&pairs).my_min_by(by_num), Some(&(1, 'a')));
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_rposition --nocapture
+    fn test_iter_rposition() {
+        let data = [1, 2, 3, 2, 1];
+        let mut iter = Iter::from(&data);
+        assert_eq!(iter.my_rposition(|&x| x == 2), Some(3)); // 👈 最后一个2
+        // 尾部已消费的元素不会再出现，剩下的仍可从头部迭代
+        assert_eq!(iter.collect::<Vec<_>>(), vec![&1, &2, &3]);
+
+        assert_eq!(Iter::from(&data).my_rposition(|&x| x == 9), None);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_collect_result_vec --nocapture
+    fn test_iter_collect_result_vec() {
+        let ok = Iter::from(&["1", "2", "3"]).my_map(|s| s.parse::<i32>());
+        assert_eq!(ok.my_collect_result_vec(), Ok(vec![1, 2, 3]));
+
+        // 返回第一个错误："x"之后的"y"不会被解析
+        let err = Iter::from(&["1", "x", "y"])
+            .my_map(|s| s.parse::<i32>().map_err(|_| *s))
+            .my_collect_result_vec();
+        assert_eq!(err, Err("x"));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    // cargo test --lib -F iterator-patterns,async -- test_iter_into_stream --nocapture
+    async fn test_iter_into_stream() {
+        use futures::StreamExt;
+
+        let doubled: Vec<i32> = Iter::from(&[1, 2, 3])
+            .my_map(|x| x * 2)
+            .into_stream()
+            .collect()
+            .await;
+        assert_eq!(doubled, vec![2, 4, 6]);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_sorted_by_key --nocapture
+    fn test_iter_sorted_by_key() {
+        let words = ["pear", "fig", "apple", "kiwi", "date", "banana"];
+        let sorted = Iter::from(&words).my_sorted_by_key(|w| w.len());
+        // 长度相同的 pear/kiwi/date 保持输入顺序
+        assert_eq!(
+            sorted,
+            vec![&"fig", &"pear", &"kiwi", &"date", &"apple", &"banana"]
+        );
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_iter_map_err --nocapture
+    fn test_iter_map_err() {
+        let res: Result<Vec<i32>, String> = Iter::from(&["1", "x"])
+            .my_map(|s| s.parse::<i32>())
+            .my_map_err(|e| format!("解析失败: {}", e))
+            .collect();
+        assert_eq!(
+            res,
+            Err("解析失败: invalid digit found in string".to_string())
+        );
+
+        let res: Result<Vec<i32>, String> = Iter::from(&["1", "2"])
+            .my_map(|s| s.parse::<i32>())
+            .my_map_err(|e| e.to_string())
+            .collect();
+        assert_eq!(res, Ok(vec![1, 2]));
+    }
 }
 
 #[test]
@@ -189,6 +1267,7 @@ fn zero_cost_benchmark() {
     assert_eq!(sum1, sum2);
 }
 
+#[cfg(feature = "std")]
 mod iter_best_practice {
     use std::error::Error;
     use std::num::ParseIntError;
@@ -238,6 +1317,29 @@ mod iter_best_practice {
         })
     }
 
+    // 最佳实践3.1：collect到Result时保留第一个错误的位置
+    // ❌ collect::<Result<Vec<_>, _>>() 只返回错误本身，不知道是第几个元素出错
+    // ✅ 返回 (下标, 错误)，方便定位
+    fn collect_results_indexed<V, E>(
+        iter: impl Iterator<Item = Result<V, E>>,
+    ) -> Result<Vec<V>, (usize, E)> {
+        let mut values = Vec::with_capacity(iter.size_hint().0);
+        for (i, item) in iter.enumerate() {
+            values.push(item.map_err(|e| (i, e))?);
+        }
+        Ok(values)
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_collect_results_indexed --nocapture
+    fn test_collect_results_indexed() {
+        let data: Vec<Result<i32, &str>> = vec![Ok(1), Ok(2), Err("bad")];
+        assert_eq!(collect_results_indexed(data.into_iter()), Err((2, "bad")));
+
+        let ok: Vec<Result<i32, &str>> = vec![Ok(1), Ok(2)];
+        assert_eq!(collect_results_indexed(ok.into_iter()), Ok(vec![1, 2]));
+    }
+
     // 最佳实践4：组合多个数据源
     fn best_practice_multiple_sources() {
         let users = vec!["Alice", "Bob", "Charlie"];
@@ -283,7 +1385,11 @@ mod iter_best_practice {
     }
 }
 
+#[cfg(feature = "std")]
 mod advance_collecting {
+    use std::collections::{HashMap, HashSet};
+    use std::hash::Hash;
+
     // 实现自定义的collect行为
     trait CollectExt<T>: Iterator<Item = T> {
         // 收集到指定容量的Vec
@@ -338,10 +1444,191 @@ mod advance_collecting {
 
             batches
         }
+
+        // 按条件分批：每加入一个元素后检查当前批次，should_flush返回true就结束这一批
+        fn collect_batched_by<F>(self, mut should_flush: F) -> Vec<Vec<T>>
+        where
+            Self: Sized,
+            F: FnMut(&[T]) -> bool,
+        {
+            let mut batches = Vec::new();
+            let mut current_batch = Vec::new();
+
+            for item in self {
+                current_batch.push(item);
+                if should_flush(&current_batch) {
+                    batches.push(std::mem::take(&mut current_batch));
+                }
+            }
+
+            if !current_batch.is_empty() {
+                batches.push(current_batch); // 👈 未触发flush的剩余元素
+            }
+
+            batches
+        }
+
+        // 按key去重，每个key保留第一次出现的元素，结果顺序与输入一致
+        fn collect_dedup_by_key<K, F>(self, mut key_fn: F) -> Vec<T>
+        where
+            Self: Sized,
+            K: Eq + Hash,
+            F: FnMut(&T) -> K,
+        {
+            let mut seen = HashSet::new();
+            self.filter(|item| seen.insert(key_fn(item))).collect()
+        }
+
+        // 限制最多收集max个元素，超出时立即返回Err(max)，防止无界输入耗尽内存
+        fn collect_bounded(self, max: usize) -> Result<Vec<T>, usize>
+        where
+            Self: Sized,
+        {
+            let mut vec = Vec::with_capacity(self.size_hint().0.min(max));
+            for item in self {
+                if vec.len() == max {
+                    return Err(max); // 👈 第max+1个元素到来时就停止拉取
+                }
+                vec.push(item);
+            }
+            Ok(vec)
+        }
+
+        // 建立 元素 -> 首次出现下标 的查找表，重复元素不会覆盖已有下标
+        fn collect_indexed_map(self) -> HashMap<T, usize>
+        where
+            Self: Sized,
+            T: Eq + Hash,
+        {
+            let mut map = HashMap::new();
+            for (i, item) in self.enumerate() {
+                map.entry(item).or_insert(i); // 👈 只记录第一次
+            }
+            map
+        }
     }
 
     impl<T, I: Iterator<Item = T>> CollectExt<T> for I {}
 
+    // 统计累加器：可以直接 collect / sum 得到
+    #[derive(Debug, Default, Clone, Copy, PartialEq)]
+    struct Stats {
+        count: usize,
+        sum: i64,
+        min: Option<i32>,
+        max: Option<i32>,
+    }
+
+    impl Stats {
+        fn push(&mut self, x: i32) {
+            self.count += 1;
+            self.sum += x as i64;
+            self.min = Some(self.min.map_or(x, |m| m.min(x)));
+            self.max = Some(self.max.map_or(x, |m| m.max(x)));
+        }
+
+        // 合并两份统计：计数/求和相加，最小/最大值取两者中的极值
+        fn merge(self, other: Stats) -> Stats {
+            let pick = |a: Option<i32>, b: Option<i32>, f: fn(i32, i32) -> i32| match (a, b) {
+                (Some(a), Some(b)) => Some(f(a, b)),
+                (a, b) => a.or(b),
+            };
+            Stats {
+                count: self.count + other.count,
+                sum: self.sum + other.sum,
+                min: pick(self.min, other.min, i32::min),
+                max: pick(self.max, other.max, i32::max),
+            }
+        }
+
+        fn mean(&self) -> Option<f64> {
+            (self.count > 0).then(|| self.sum as f64 / self.count as f64)
+        }
+    }
+
+    impl FromIterator<i32> for Stats {
+        fn from_iter<I: IntoIterator<Item = i32>>(iter: I) -> Self {
+            let mut stats = Stats::default();
+            for x in iter {
+                stats.push(x);
+            }
+            stats
+        }
+    }
+
+    // ✅ 支持 iter.sum::<Stats>()
+    impl std::iter::Sum<i32> for Stats {
+        fn sum<I: Iterator<Item = i32>>(iter: I) -> Self {
+            iter.map(|x| Stats::from_iter([x]))
+                .fold(Stats::default(), Stats::merge) // 👈 逐个合并
+        }
+    }
+
+    // 多份统计也可以直接求和（比如分片统计后汇总）
+    impl std::iter::Sum<Stats> for Stats {
+        fn sum<I: Iterator<Item = Stats>>(iter: I) -> Self {
+            iter.fold(Stats::default(), Stats::merge)
+        }
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_stats_sum --nocapture
+    fn test_stats_sum() {
+        let summed: Stats = (1..=10).sum();
+        let collected: Stats = (1..=10).collect();
+
+        assert_eq!(summed, collected);
+        assert_eq!(summed.count, 10);
+        assert_eq!(summed.sum, 55);
+        assert_eq!(summed.min, Some(1));
+        assert_eq!(summed.max, Some(10));
+        assert_eq!(summed.mean(), Some(5.5));
+
+        // 分片统计后再合并，结果一致
+        let sharded: Stats = [(1..=4).collect::<Stats>(), (5..=10).collect()]
+            .into_iter()
+            .sum();
+        assert_eq!(sharded, collected);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_collect_dedup_by_key --nocapture
+    fn test_collect_dedup_by_key() {
+        let data = vec![("a", 1), ("b", 2), ("a", 3)];
+        let res = data.into_iter().collect_dedup_by_key(|&(name, _)| name);
+        assert_eq!(res, vec![("a", 1), ("b", 2)]);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_collect_batched_by --nocapture
+    fn test_collect_batched_by() {
+        let batches = [5, 6, 2, 3, 9]
+            .into_iter()
+            .collect_batched_by(|batch| batch.iter().sum::<i32>() > 10);
+        assert_eq!(batches, vec![vec![5, 6], vec![2, 3, 9]]);
+
+        let rest = [1, 2].into_iter().collect_batched_by(|_| false);
+        assert_eq!(rest, vec![vec![1, 2]]);
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_collect_indexed_map --nocapture
+    fn test_collect_indexed_map() {
+        let map = ["a", "b", "a", "c"].into_iter().collect_indexed_map();
+        assert_eq!(map, HashMap::from([("a", 0), ("b", 1), ("c", 3)]));
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_collect_bounded --nocapture
+    fn test_collect_bounded() {
+        assert_eq!((0..5).collect_bounded(10), Ok(vec![0, 1, 2, 3, 4]));
+
+        let mut pulled = 0;
+        let res = (0..100).inspect(|_| pulled += 1).collect_bounded(10);
+        assert_eq!(res, Err(10));
+        assert_eq!(pulled, 11);
+    }
+
     fn advanced_collect_examples() {
         let data: Vec<i32> = (1..=20).collect();
 
@@ -361,6 +1648,7 @@ mod advance_collecting {
     }
 }
 
+#[cfg(feature = "std")]
 mod iterator_and_generator {
 
     #[test]
@@ -397,4 +1685,105 @@ mod iterator_and_generator {
         let first_32: Vec<_> = fibonacci_gen.take(32).collect();
         println!("前32个斐波那契数: {:?}", first_32);
     }
+
+    // ✅ 稳定版写法：用successors代替gen块，输出与generator_to_iterator一致
+    pub fn fibonacci() -> impl Iterator<Item = u64> {
+        std::iter::successors(Some((0u64, 1u64)), |&(a, b)| {
+            a.checked_add(b).map(|next| (b, next)) // 👈 溢出时返回None，迭代结束
+        })
+        .map(|(_, b)| b)
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_fibonacci_successors --nocapture
+    fn test_fibonacci_successors() {
+        let first_10: Vec<u64> = fibonacci().take(10).collect();
+        assert_eq!(first_10, vec![1, 1, 2, 3, 5, 8, 13, 21, 34, 55]);
+        assert_eq!(fibonacci().count(), 93); // 👈 不会panic
+    }
+
+    // ✅ 稳定版写法：手动实现Iterator
+    // 二阶线性递推 x(n+2) = coeff_a * x(n) + coeff_b * x(n+1)
+    // 选择不同的初始值/系数即可得到斐波那契、卢卡斯、佩尔数列
+    struct LinearRecurrence {
+        a: u64,
+        b: u64,
+        coeff_a: u64,
+        coeff_b: u64,
+        remaining: Option<u8>, // 👈 后续项溢出后，只剩还没输出的有限几项
+    }
+
+    impl LinearRecurrence {
+        fn new(a: u64, b: u64, coeff_a: u64, coeff_b: u64) -> Self {
+            Self {
+                a,
+                b,
+                coeff_a,
+                coeff_b,
+                remaining: None,
+            }
+        }
+
+        fn fibonacci() -> Self {
+            Self::new(1, 1, 1, 1)
+        }
+
+        fn lucas() -> Self {
+            Self::new(2, 1, 1, 1)
+        }
+
+        fn pell() -> Self {
+            Self::new(0, 1, 1, 2)
+        }
+    }
+
+    impl Iterator for LinearRecurrence {
+        type Item = u64;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let cur = self.a;
+            match self.remaining {
+                Some(0) => return None,
+                Some(n) => {
+                    self.remaining = Some(n - 1);
+                    self.a = self.b;
+                    return Some(cur);
+                }
+                None => {}
+            }
+
+            // 使用checked运算，溢出时结束而不是panic或回绕
+            let next = self
+                .coeff_a
+                .checked_mul(self.a)
+                .zip(self.coeff_b.checked_mul(self.b))
+                .and_then(|(x, y)| x.checked_add(y));
+            self.a = self.b;
+            match next {
+                Some(next) => self.b = next,
+                None => self.remaining = Some(1), // 👈 b仍是有效项，输出后结束
+            }
+            Some(cur)
+        }
+    }
+
+    #[test]
+    // cargo test --lib -F iterator-patterns -- test_linear_recurrence --nocapture
+    fn test_linear_recurrence() {
+        let fib: Vec<u64> = LinearRecurrence::fibonacci().take(10).collect();
+        assert_eq!(fib, vec![1, 1, 2, 3, 5, 8, 13, 21, 34, 55]);
+
+        let lucas: Vec<u64> = LinearRecurrence::lucas().take(8).collect();
+        assert_eq!(lucas, vec![2, 1, 3, 4, 7, 11, 18, 29]);
+
+        let pell: Vec<u64> = LinearRecurrence::pell().take(6).collect();
+        assert_eq!(pell, vec![0, 1, 2, 5, 12, 29]);
+
+        // u64能表示到第93个斐波那契数，之后结束而不是溢出
+        assert_eq!(LinearRecurrence::fibonacci().count(), 93);
+        assert_eq!(
+            LinearRecurrence::fibonacci().last(),
+            Some(12_200_160_415_121_876_738)
+        );
+    }
 }
@@ -1,3 +1,6 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![feature(gen_blocks)]
 
+extern crate alloc;
+
 mod examples;